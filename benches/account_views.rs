@@ -0,0 +1,95 @@
+use blaze_meteora_test_task::account_view::{PoolView, QuoteViews, VaultView};
+use blaze_meteora_test_task::compute_quote;
+use blaze_meteora_test_task::decoder::{decode_clock, decode_pool, decode_vault};
+use blaze_meteora_test_task::snapshot::QuoteSnapshot;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+const FIXTURE: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/fixtures/synthetic_usdc_wsol.json"
+);
+
+/// Read the pool fields needed to quote, deserializing the whole pool or through the view
+fn pool(c: &mut Criterion) {
+    let snapshot = QuoteSnapshot::load(FIXTURE).unwrap();
+    let data = snapshot.pool.data.as_slice();
+
+    let mut group = c.benchmark_group("pool");
+    group.bench_function("borsh", |b| {
+        b.iter(|| {
+            let pool = decode_pool(black_box(data)).unwrap();
+            (
+                pool.enabled,
+                pool.token_a_mint,
+                pool.token_b_mint,
+                pool.fees,
+                pool.bootstrapping.activation_point,
+            )
+        })
+    });
+    group.bench_function("view", |b| {
+        b.iter(|| {
+            let pool = PoolView::new(black_box(data)).unwrap();
+            (
+                pool.enabled(),
+                pool.token_a_mint(),
+                pool.token_b_mint(),
+                pool.fees(),
+                pool.activation_point(),
+            )
+        })
+    });
+    group.finish();
+}
+
+/// Convert a vault LP amount into tokens, deserializing the whole vault or through the view
+fn vault(c: &mut Criterion) {
+    let snapshot = QuoteSnapshot::load(FIXTURE).unwrap();
+    let data = snapshot.vault_b.data.as_slice();
+    let current_time = decode_clock(&snapshot.clock.data).unwrap().unix_timestamp as u64;
+
+    let mut group = c.benchmark_group("vault");
+    group.bench_function("borsh", |b| {
+        b.iter(|| {
+            decode_vault(black_box(data)).unwrap().get_amount_by_share(
+                current_time,
+                1_000_000,
+                10_000_000,
+            )
+        })
+    });
+    group.bench_function("view", |b| {
+        b.iter(|| {
+            VaultView::new(black_box(data))
+                .unwrap()
+                .get_amount_by_share(current_time, 1_000_000, 10_000_000)
+        })
+    });
+    group.finish();
+}
+
+/// Quote a swap from the snapshot accounts, decoding them or reading them through the views
+fn quote(c: &mut Criterion) {
+    let snapshot = QuoteSnapshot::load(FIXTURE).unwrap();
+    let in_token_mint = decode_pool(&snapshot.pool.data).unwrap().token_b_mint;
+
+    let mut group = c.benchmark_group("quote");
+    group.bench_function("borsh", |b| {
+        b.iter(|| {
+            let quote_data = black_box(&snapshot).to_quote_data().unwrap();
+            compute_quote(in_token_mint, 1_000_000_000, quote_data).unwrap()
+        })
+    });
+    group.bench_function("view", |b| {
+        b.iter(|| {
+            QuoteViews::from_snapshot(black_box(&snapshot))
+                .unwrap()
+                .compute_quote(in_token_mint, 1_000_000_000)
+                .unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, pool, vault, quote);
+criterion_main!(benches);
@@ -0,0 +1,143 @@
+use blaze_meteora_test_task::decoder::*;
+use blaze_meteora_test_task::pda_resolver::PdaResolver;
+use blaze_meteora_test_task::quote_ladder::{compute_ladder, geometric_amounts};
+use blaze_meteora_test_task::quote_service::{
+    compute_quote, derive_pool_address_with_config, get_all_accounts_for_quote, QuoteData,
+};
+use blaze_meteora_test_task::router::Router;
+use blaze_meteora_test_task::snapshot::QuoteSnapshot;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use solana_program::pubkey::Pubkey;
+
+fn load(name: &str) -> QuoteSnapshot {
+    QuoteSnapshot::load(format!(
+        "{}/fixtures/{}.json",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    ))
+    .unwrap()
+}
+
+fn quote_data(name: &str) -> (Pubkey, QuoteData) {
+    let snapshot = load(name);
+    (snapshot.pool.address, snapshot.to_quote_data().unwrap())
+}
+
+/// Decoding of every account read for a quote
+fn decode(c: &mut Criterion) {
+    let snapshot = load("synthetic_usdc_wsol");
+
+    let mut group = c.benchmark_group("decode");
+    group.bench_function("pool", |b| {
+        b.iter(|| decode_pool(black_box(&snapshot.pool.data)))
+    });
+    group.bench_function("curve_type", |b| {
+        b.iter(|| decode_curve_type(black_box(&snapshot.pool.data)))
+    });
+    group.bench_function("vault", |b| {
+        b.iter(|| decode_vault(black_box(&snapshot.vault_a.data)))
+    });
+    group.bench_function("mint", |b| {
+        b.iter(|| decode_mint(black_box(&snapshot.vault_a_lp_mint.data)))
+    });
+    group.bench_function("token_account", |b| {
+        b.iter(|| decode_token_account(black_box(&snapshot.vault_a_token.data)))
+    });
+    group.bench_function("clock", |b| {
+        b.iter(|| decode_clock(black_box(&snapshot.clock.data)))
+    });
+    // Decoding and checking the whole snapshot, as done for every RPC quote
+    group.bench_function("snapshot", |b| {
+        b.iter(|| black_box(&snapshot).to_quote_data())
+    });
+    group.finish();
+}
+
+/// PDA derivation of the accounts of a pool
+fn pda(c: &mut Criterion) {
+    let (pool_address, quote_data) = quote_data("synthetic_usdc_wsol");
+    let (token_a_mint, token_b_mint) = (quote_data.pool.token_a_mint, quote_data.pool.token_b_mint);
+
+    let mut group = c.benchmark_group("pda");
+    group.bench_function("get_all_accounts_for_quote", |b| {
+        b.iter(|| {
+            get_all_accounts_for_quote(
+                black_box(token_a_mint),
+                black_box(token_b_mint),
+                black_box(pool_address),
+            )
+        })
+    });
+    // Cache hit of the resolver, after the accounts were derived once
+    let pda_resolver = PdaResolver::new();
+    pda_resolver.resolve(token_a_mint, token_b_mint, pool_address);
+    group.bench_function("resolver_cached", |b| {
+        b.iter(|| {
+            pda_resolver.resolve(
+                black_box(token_a_mint),
+                black_box(token_b_mint),
+                black_box(pool_address),
+            )
+        })
+    });
+    group.bench_function("derive_pool_address_with_config", |b| {
+        b.iter(|| {
+            derive_pool_address_with_config(
+                black_box(token_a_mint),
+                black_box(token_b_mint),
+                black_box(Pubkey::default()),
+            )
+        })
+    });
+    group.finish();
+}
+
+/// Pure quote on the decoded snapshot
+fn quote(c: &mut Criterion) {
+    let (_, quote_data) = quote_data("synthetic_usdc_wsol");
+    let (token_a_mint, token_b_mint) = (quote_data.pool.token_a_mint, quote_data.pool.token_b_mint);
+
+    let mut group = c.benchmark_group("compute_quote");
+    group.bench_function("a_to_b", |b| {
+        b.iter(|| compute_quote(token_a_mint, black_box(100_000_000), quote_data.clone()).unwrap())
+    });
+    group.bench_function("b_to_a", |b| {
+        b.iter(|| {
+            compute_quote(token_b_mint, black_box(1_000_000_000), quote_data.clone()).unwrap()
+        })
+    });
+    group.finish();
+}
+
+/// Quote ladder over 20 amounts and a two hops route
+fn ladder_and_route(c: &mut Criterion) {
+    let usdc_wsol = quote_data("synthetic_usdc_wsol");
+    let metav_wsol = quote_data("synthetic_metav_wsol");
+    let wsol_mint = usdc_wsol.1.pool.token_b_mint;
+    let usdc_mint = usdc_wsol.1.pool.token_a_mint;
+    let metav_mint = if metav_wsol.1.pool.token_a_mint == wsol_mint {
+        metav_wsol.1.pool.token_b_mint
+    } else {
+        metav_wsol.1.pool.token_a_mint
+    };
+    let amounts = geometric_amounts(1_000_000, 1_000_000_000_000, 20);
+
+    c.bench_function("ladder", |b| {
+        b.iter(|| compute_ladder(wsol_mint, black_box(&amounts), &usdc_wsol.1).unwrap())
+    });
+    // A new router for each iteration, so the route is searched and not read from its cache
+    c.bench_function("route", |b| {
+        b.iter_batched(
+            || Router::new(vec![usdc_wsol.clone(), metav_wsol.clone()]),
+            |mut router| {
+                router
+                    .find_best_route(metav_mint, usdc_mint, black_box(1_000_000_000), 2)
+                    .unwrap()
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+criterion_group!(benches, decode, pda, quote, ladder_and_route);
+criterion_main!(benches);
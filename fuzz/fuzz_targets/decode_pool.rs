@@ -0,0 +1,15 @@
+#![no_main]
+
+use blaze_meteora_test_task::decoder::{decode_curve_type, decode_pool};
+use blaze_meteora_test_task::state::Pool;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = decode_pool(data);
+    let _ = decode_curve_type(data);
+
+    // Also feed the bytes behind a valid discriminator, so the fields are reached
+    let account = [&Pool::discriminator()[..], data].concat();
+    let _ = decode_pool(&account);
+    let _ = decode_curve_type(&account);
+});
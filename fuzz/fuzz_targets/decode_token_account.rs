@@ -0,0 +1,8 @@
+#![no_main]
+
+use blaze_meteora_test_task::decoder::decode_token_account;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = decode_token_account(data);
+});
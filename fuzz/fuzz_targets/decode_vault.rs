@@ -0,0 +1,12 @@
+#![no_main]
+
+use blaze_meteora_test_task::decoder::decode_vault;
+use blaze_meteora_test_task::state::Vault;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = decode_vault(data);
+
+    // Also feed the bytes behind a valid discriminator, so the fields are reached
+    let _ = decode_vault(&[&Vault::discriminator()[..], data].concat());
+});
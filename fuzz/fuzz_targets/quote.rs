@@ -0,0 +1,107 @@
+#![no_main]
+
+use anchor_spl::token::spl_token;
+use anchor_spl::token::spl_token::solana_program::program_pack::Pack;
+use arbitrary::Arbitrary;
+use blaze_meteora_test_task::decoder::*;
+use blaze_meteora_test_task::pool_validation::validate_quote_data;
+use blaze_meteora_test_task::quote_service::{compute_quote, get_pool_reserves, QuoteData};
+use blaze_meteora_test_task::state::{Pool, Vault};
+use libfuzzer_sys::fuzz_target;
+
+/// Accounts of a quote. Pool and vault fields are raw bytes of the decoded size, behind a valid
+/// discriminator, while mint and token accounts are packed from arbitrary amounts, so decoding
+/// succeeds often enough to reach the quote math. Raw accounts of any size are covered by the
+/// decoder targets.
+#[derive(Arbitrary, Debug)]
+struct Input {
+    pool: [u8; POOL_FIELDS_END - 8],
+    /// Bytes from the curve type offset of the pool account, enough for any curve
+    curve_type: [u8; 64],
+    vault_a: [u8; VAULT_FIELDS_END - 8],
+    vault_b: [u8; VAULT_FIELDS_END - 8],
+    vault_a_lp_mint_supply: u64,
+    vault_b_lp_mint_supply: u64,
+    pool_vault_a_lp_amount: u64,
+    pool_vault_b_lp_amount: u64,
+    vault_a_token_amount: u64,
+    vault_b_token_amount: u64,
+    clock: [u8; CLOCK_ACCOUNT_SIZE],
+    a_to_b: bool,
+    in_amount: u64,
+}
+
+fn mint_data(supply: u64) -> Vec<u8> {
+    let mut data = vec![0; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        supply,
+        is_initialized: true,
+        ..Default::default()
+    }
+    .pack_into_slice(&mut data);
+    data
+}
+
+fn token_account_data(amount: u64) -> Vec<u8> {
+    let mut data = vec![0; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        amount,
+        state: spl_token::state::AccountState::Initialized,
+        ..Default::default()
+    }
+    .pack_into_slice(&mut data);
+    data
+}
+
+impl Input {
+    fn quote_data(&self) -> anyhow::Result<QuoteData> {
+        let mut pool_data = [&Pool::discriminator()[..], &self.pool].concat();
+        pool_data.resize(POOL_CURVE_TYPE_OFFSET, 0);
+        pool_data.extend_from_slice(&self.curve_type[..]);
+
+        Ok(QuoteData {
+            pool: decode_pool(&pool_data)?,
+            curve_type: decode_curve_type(&pool_data)?,
+            vault_a: decode_vault(&[&Vault::discriminator()[..], &self.vault_a[..]].concat())?,
+            vault_b: decode_vault(&[&Vault::discriminator()[..], &self.vault_b[..]].concat())?,
+            pool_vault_a_lp_token: decode_token_account(&token_account_data(
+                self.pool_vault_a_lp_amount,
+            ))?,
+            pool_vault_b_lp_token: decode_token_account(&token_account_data(
+                self.pool_vault_b_lp_amount,
+            ))?,
+            vault_a_lp_mint_supply: decode_mint(&mint_data(self.vault_a_lp_mint_supply))?.supply,
+            vault_b_lp_mint_supply: decode_mint(&mint_data(self.vault_b_lp_mint_supply))?.supply,
+            vault_a_token: decode_token_account(&token_account_data(self.vault_a_token_amount))?,
+            vault_b_token: decode_token_account(&token_account_data(self.vault_b_token_amount))?,
+            clock: decode_clock(&self.clock)?,
+        })
+    }
+}
+
+fuzz_target!(|input: Input| {
+    let Ok(quote_data) = input.quote_data() else {
+        return;
+    };
+
+    validate_quote_data(&quote_data);
+
+    let in_token_mint = if input.a_to_b {
+        quote_data.pool.token_a_mint
+    } else {
+        quote_data.pool.token_b_mint
+    };
+    let reserves = get_pool_reserves(&quote_data);
+
+    if let Ok(quote_result) = compute_quote(in_token_mint, input.in_amount, quote_data.clone()) {
+        // A quote can never take more than the pool holds. The direction is resolved like the
+        // quote does, which matters for pools with the same mint on both sides.
+        let (token_a_amount, token_b_amount) = reserves.unwrap();
+        let out_reserve = if in_token_mint == quote_data.pool.token_a_mint {
+            token_b_amount
+        } else {
+            token_a_amount
+        };
+        assert!(quote_result.out_amount <= out_reserve);
+    }
+});
@@ -0,0 +1,52 @@
+/// Trade and protocol fees of a pool
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolFees {
+    /// Trade fee numerator
+    pub trade_fee_numerator: u64,
+    /// Trade fee denominator
+    pub trade_fee_denominator: u64,
+    /// Owner trade fee numerator
+    pub protocol_trade_fee_numerator: u64,
+    /// Owner trade fee denominator
+    pub protocol_trade_fee_denominator: u64,
+}
+
+impl PoolFees {
+    /// Calculate the trading fee in trading tokens
+    pub fn trading_fee(&self, trading_tokens: u128) -> Option<u128> {
+        calculate_fee(
+            trading_tokens,
+            u128::from(self.trade_fee_numerator),
+            u128::from(self.trade_fee_denominator),
+        )
+    }
+
+    /// Calculate the protocol trading fee in trading tokens
+    pub fn protocol_trading_fee(&self, trading_tokens: u128) -> Option<u128> {
+        calculate_fee(
+            trading_tokens,
+            u128::from(self.protocol_trade_fee_numerator),
+            u128::from(self.protocol_trade_fee_denominator),
+        )
+    }
+}
+
+/// Helper function for calculating swap fee
+pub fn calculate_fee(
+    token_amount: u128,
+    fee_numerator: u128,
+    fee_denominator: u128,
+) -> Option<u128> {
+    if fee_numerator == 0 || token_amount == 0 {
+        Some(0)
+    } else {
+        let fee = token_amount
+            .checked_mul(fee_numerator)?
+            .checked_div(fee_denominator)?;
+        if fee == 0 {
+            Some(1) // minimum fee of one token
+        } else {
+            Some(fee)
+        }
+    }
+}
@@ -0,0 +1,16 @@
+//! Swap, fee and vault math of Meteora AMM volatile pools.
+//!
+//! `no_std` and allocation-free, so it can run inside an on-chain program. The off-chain
+//! quoter computes its quotes with these same functions.
+#![no_std]
+
+mod fee;
+mod swap;
+mod vault;
+
+pub use fee::*;
+pub use swap::*;
+pub use vault::*;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,97 @@
+pub struct ConstantProduct {}
+
+impl SwapCurve for ConstantProduct {
+    fn swap(
+        &self,
+        source_amount: u64,
+        swap_source_amount: u64,
+        swap_destination_amount: u64,
+        _trade_direction: TradeDirection,
+    ) -> Option<SwapResult> {
+        // Convert to u128 to prevent overflow during calculations
+        let source_amount = source_amount as u128;
+        let swap_source_amount = swap_source_amount as u128;
+        let swap_destination_amount = swap_destination_amount as u128;
+
+        // Calculate new source amount after adding the input
+        let new_swap_source_amount = swap_source_amount.checked_add(source_amount)?;
+
+        // Calculate new destination amount using constant product formula:
+        // (x + dx) * (y - dy) = x * y
+        // where x = swap_source_amount, y = swap_destination_amount, dx = source_amount.
+        // Same as the program, the new destination amount is rounded up so the invariant never
+        // decreases, and the source amount is reduced to what is needed to reach it.
+        let invariant = swap_source_amount.checked_mul(swap_destination_amount)?;
+        let (new_swap_destination_amount, new_swap_source_amount) =
+            checked_ceil_div(invariant, new_swap_source_amount)?;
+
+        let source_amount_swapped = new_swap_source_amount.checked_sub(swap_source_amount)?;
+
+        // Calculate how much destination token we get
+        let destination_amount_swapped =
+            swap_destination_amount.checked_sub(new_swap_destination_amount)?;
+        if destination_amount_swapped == 0 {
+            return None;
+        }
+
+        Some(SwapResult {
+            new_swap_source_amount,
+            new_swap_destination_amount,
+            source_amount_swapped,
+            destination_amount_swapped,
+        })
+    }
+}
+
+/// Divide rounding up, returning the quotient and the smallest divisor giving the same quotient.
+/// Same as `CheckedCeilDiv` of spl-math, a dividend smaller than the divisor gives a quotient of 1
+/// with a zero divisor if it is at least half the divisor, and of 0 otherwise.
+pub(crate) fn checked_ceil_div(dividend: u128, divisor: u128) -> Option<(u128, u128)> {
+    let mut quotient = dividend.checked_div(divisor)?;
+    if quotient == 0 {
+        if dividend.checked_mul(2)? >= divisor {
+            return Some((1, 0));
+        }
+        return Some((0, 0));
+    }
+
+    let mut divisor = divisor;
+    if dividend.checked_rem(divisor)? > 0 {
+        quotient = quotient.checked_add(1)?;
+        divisor = dividend.checked_div(quotient)?;
+        if dividend.checked_rem(quotient)? > 0 {
+            divisor = divisor.checked_add(1)?;
+        }
+    }
+
+    Some((quotient, divisor))
+}
+
+pub trait SwapCurve {
+    fn swap(
+        &self,
+        source_amount: u64,
+        swap_source_amount: u64,
+        swap_destination_amount: u64,
+        trade_direction: TradeDirection,
+    ) -> Option<SwapResult>;
+}
+
+/// Encodes all results of swapping
+#[derive(Debug, PartialEq)]
+pub struct SwapResult {
+    /// New amount of source token
+    pub new_swap_source_amount: u128,
+    /// New amount of destination token
+    pub new_swap_destination_amount: u128,
+    /// Amount of source token swapped (includes fees)
+    pub source_amount_swapped: u128,
+    /// Amount of destination token swapped
+    pub destination_amount_swapped: u128,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TradeDirection {
+    AtoB,
+    BtoA,
+}
@@ -0,0 +1,94 @@
+use super::*;
+use crate::swap::checked_ceil_div;
+
+#[test]
+fn constant_product_rounds_like_the_program() {
+    let curve = ConstantProduct {};
+    let result = curve
+        .swap(1_000, 1_000_000, 2_000_000, TradeDirection::AtoB)
+        .unwrap();
+
+    // ceil(2e12 / 1_001_000) = 1_998_002, so 1_001_000 is already the smallest source reaching it
+    assert_eq!(result.new_swap_destination_amount, 1_998_002);
+    assert_eq!(result.destination_amount_swapped, 1_998);
+    assert_eq!(result.source_amount_swapped, 1_000);
+    assert!(
+        result.new_swap_source_amount * result.new_swap_destination_amount >= 1_000_000 * 2_000_000
+    );
+}
+
+#[test]
+fn constant_product_rejects_zero_output() {
+    let curve = ConstantProduct {};
+
+    assert_eq!(curve.swap(1, 1_000_000, 1_000, TradeDirection::BtoA), None);
+    // The invariant is smaller than the new source amount, so the source amount swapped underflows
+    assert_eq!(curve.swap(10, 1, 1, TradeDirection::AtoB), None);
+}
+
+#[test]
+fn ceil_div_of_a_smaller_dividend_rounds_like_spl_math() {
+    assert_eq!(checked_ceil_div(1, 3), Some((0, 0)));
+    assert_eq!(checked_ceil_div(2, 3), Some((1, 0)));
+    assert_eq!(checked_ceil_div(3, 3), Some((1, 3)));
+    assert_eq!(checked_ceil_div(7, 3), Some((3, 3)));
+    assert_eq!(checked_ceil_div(1, 0), None);
+}
+
+#[test]
+fn constant_product_with_an_empty_source_reserve_gives_the_destination_reserve() {
+    let curve = ConstantProduct {};
+
+    // A zero invariant divides to (0, 0), so nothing is swapped in for the whole reserve
+    let result = curve.swap(1_000, 0, 1_000, TradeDirection::AtoB).unwrap();
+    assert_eq!(result.source_amount_swapped, 0);
+    assert_eq!(result.destination_amount_swapped, 1_000);
+}
+
+#[test]
+fn fee_charges_at_least_one_token() {
+    let fees = PoolFees {
+        trade_fee_numerator: 25,
+        trade_fee_denominator: 10_000,
+        protocol_trade_fee_numerator: 0,
+        protocol_trade_fee_denominator: 10_000,
+    };
+
+    assert_eq!(fees.trading_fee(0), Some(0));
+    assert_eq!(fees.trading_fee(1), Some(1));
+    assert_eq!(fees.trading_fee(1_000_000), Some(2_500));
+    assert_eq!(fees.protocol_trading_fee(1_000_000), Some(0));
+    assert_eq!(calculate_fee(1, 1, 0), None);
+}
+
+#[test]
+fn locked_profit_degrades_linearly() {
+    let tracker = LockedProfitTracker {
+        last_updated_locked_profit: 1_000_000,
+        last_report: 100,
+        // fully unlocked after 1_000 seconds
+        locked_profit_degradation: 1_000_000_000,
+    };
+
+    assert_eq!(tracker.calculate_locked_profit(100), Some(1_000_000));
+    assert_eq!(tracker.calculate_locked_profit(600), Some(500_000));
+    assert_eq!(tracker.calculate_locked_profit(1_100), Some(0));
+    assert_eq!(tracker.calculate_locked_profit(99), None);
+}
+
+#[test]
+fn vault_share_math_excludes_locked_profit() {
+    let vault = Vault {
+        total_amount: 2_000_000,
+        locked_profit_tracker: LockedProfitTracker {
+            last_updated_locked_profit: 1_000_000,
+            last_report: 100,
+            locked_profit_degradation: 1_000_000_000,
+        },
+    };
+
+    assert_eq!(vault.get_unlocked_amount(600), Some(1_500_000));
+    assert_eq!(vault.get_amount_by_share(600, 500, 1_000), Some(750_000));
+    assert_eq!(vault.get_unmint_amount(600, 750_000, 1_000), Some(500));
+    assert_eq!(vault.get_amount_by_share(600, 500, 0), None);
+}
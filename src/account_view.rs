@@ -0,0 +1,412 @@
+use crate::decoder::*;
+use crate::quote_service::{
+    compute_swap, ensure_swappable, get_trade_direction, PoolSide, QuoteResult, SwapAmounts,
+};
+use crate::snapshot::QuoteSnapshot;
+use crate::state::*;
+use anyhow::ensure;
+use solana_program::clock::Clock;
+use solana_program::pubkey::Pubkey;
+use solana_program::sysvar;
+
+/// Offsets of the pool fields in the pool account, discriminator included
+mod pool_offset {
+    pub const LP_MINT: usize = 8;
+    pub const TOKEN_A_MINT: usize = 40;
+    pub const TOKEN_B_MINT: usize = 72;
+    pub const A_VAULT: usize = 104;
+    pub const B_VAULT: usize = 136;
+    pub const A_VAULT_LP: usize = 168;
+    pub const B_VAULT_LP: usize = 200;
+    pub const ENABLED: usize = 233;
+    pub const FEES: usize = 330;
+    pub const POOL_TYPE: usize = 362;
+    pub const ACTIVATION_POINT: usize = 403;
+    pub const WHITELISTED_VAULT: usize = 411;
+    pub const ACTIVATION_TYPE: usize = 475;
+}
+
+/// Offsets of the vault fields in the vault account, discriminator included
+mod vault_offset {
+    pub const ENABLED: usize = 8;
+    pub const TOTAL_AMOUNT: usize = 11;
+    pub const TOKEN_VAULT: usize = 19;
+    pub const TOKEN_MINT: usize = 83;
+    pub const LP_MINT: usize = 115;
+    pub const LOCKED_PROFIT_TRACKER: usize = 1203;
+}
+
+/// Read-only view over the raw bytes of a pool account, reading the fields needed to quote
+/// in place instead of deserializing the whole pool
+#[derive(Debug, Clone, Copy)]
+pub struct PoolView<'a> {
+    data: &'a [u8; POOL_FIELDS_END],
+    /// Whole account, the curve type lies after the pool fields
+    account: &'a [u8],
+}
+
+impl<'a> PoolView<'a> {
+    /// Check the discriminator and the size of the pool account, and that the enabled flag and
+    /// pool type are valid like Borsh requires
+    pub fn new(account: &'a [u8]) -> anyhow::Result<Self> {
+        let data = account_data(account, Pool::discriminator(), "pool")?;
+        ensure!(
+            data[pool_offset::ENABLED] <= 1,
+            "Invalid pool enabled flag {}",
+            data[pool_offset::ENABLED]
+        );
+        ensure!(
+            data[pool_offset::POOL_TYPE] <= PoolType::Permissionless as u8,
+            "Invalid pool type {}",
+            data[pool_offset::POOL_TYPE]
+        );
+
+        Ok(Self { data, account })
+    }
+
+    /// LP token mint of the pool
+    pub fn lp_mint(&self) -> Pubkey {
+        read_pubkey(self.data, pool_offset::LP_MINT)
+    }
+
+    /// Token A mint of the pool
+    pub fn token_a_mint(&self) -> Pubkey {
+        read_pubkey(self.data, pool_offset::TOKEN_A_MINT)
+    }
+
+    /// Token B mint of the pool
+    pub fn token_b_mint(&self) -> Pubkey {
+        read_pubkey(self.data, pool_offset::TOKEN_B_MINT)
+    }
+
+    /// Vault account for token A
+    pub fn a_vault(&self) -> Pubkey {
+        read_pubkey(self.data, pool_offset::A_VAULT)
+    }
+
+    /// Vault account for token B
+    pub fn b_vault(&self) -> Pubkey {
+        read_pubkey(self.data, pool_offset::B_VAULT)
+    }
+
+    /// LP token account of vault A
+    pub fn a_vault_lp(&self) -> Pubkey {
+        read_pubkey(self.data, pool_offset::A_VAULT_LP)
+    }
+
+    /// LP token account of vault B
+    pub fn b_vault_lp(&self) -> Pubkey {
+        read_pubkey(self.data, pool_offset::B_VAULT_LP)
+    }
+
+    /// Whether the pool is enabled
+    pub fn enabled(&self) -> bool {
+        self.data[pool_offset::ENABLED] == 1
+    }
+
+    /// Fee charges setting of the pool
+    pub fn fees(&self) -> PoolFees {
+        PoolFees {
+            trade_fee_numerator: read_u64(self.data, pool_offset::FEES),
+            trade_fee_denominator: read_u64(self.data, pool_offset::FEES + 8),
+            protocol_trade_fee_numerator: read_u64(self.data, pool_offset::FEES + 16),
+            protocol_trade_fee_denominator: read_u64(self.data, pool_offset::FEES + 24),
+        }
+    }
+
+    /// Activation point of the pool, slot or timestamp depending on the activation type
+    pub fn activation_point(&self) -> u64 {
+        read_u64(self.data, pool_offset::ACTIVATION_POINT)
+    }
+
+    /// Vault allowed to buy before the activation point
+    pub fn whitelisted_vault(&self) -> Pubkey {
+        read_pubkey(self.data, pool_offset::WHITELISTED_VAULT)
+    }
+
+    /// Activation type, 0 means by slot, 1 means by timestamp
+    pub fn activation_type(&self) -> u8 {
+        self.data[pool_offset::ACTIVATION_TYPE]
+    }
+
+    /// Swap curve of the pool, decoded from the end of the account
+    pub fn curve_type(&self) -> anyhow::Result<CurveType> {
+        decode_curve_type(self.account)
+    }
+
+    /// Activation settings of the pool, without the deprecated pool creator
+    fn bootstrapping(&self) -> Bootstrapping {
+        Bootstrapping {
+            activation_point: self.activation_point(),
+            whitelisted_vault: self.whitelisted_vault(),
+            activation_type: self.activation_type(),
+            ..Bootstrapping::default()
+        }
+    }
+}
+
+/// Read-only view over the raw bytes of a vault account, skipping the strategies
+#[derive(Debug, Clone, Copy)]
+pub struct VaultView<'a> {
+    data: &'a [u8; VAULT_FIELDS_END],
+}
+
+impl<'a> VaultView<'a> {
+    /// Check the discriminator and the size of the vault account
+    pub fn new(data: &'a [u8]) -> anyhow::Result<Self> {
+        Ok(Self {
+            data: account_data(data, Vault::discriminator(), "vault")?,
+        })
+    }
+
+    /// Vault enabled flag, deposits are refused when zero
+    pub fn enabled(&self) -> u8 {
+        self.data[vault_offset::ENABLED]
+    }
+
+    /// The total liquidity of the vault
+    pub fn total_amount(&self) -> u64 {
+        read_u64(self.data, vault_offset::TOTAL_AMOUNT)
+    }
+
+    /// Token account holding the liquidity in vault reserve
+    pub fn token_vault(&self) -> Pubkey {
+        read_pubkey(self.data, vault_offset::TOKEN_VAULT)
+    }
+
+    /// Token mint that vault supports
+    pub fn token_mint(&self) -> Pubkey {
+        read_pubkey(self.data, vault_offset::TOKEN_MINT)
+    }
+
+    /// Lp mint of vault
+    pub fn lp_mint(&self) -> Pubkey {
+        read_pubkey(self.data, vault_offset::LP_MINT)
+    }
+
+    /// Locked profit information of the vault
+    pub fn locked_profit_tracker(&self) -> LockedProfitTracker {
+        LockedProfitTracker {
+            last_updated_locked_profit: read_u64(self.data, vault_offset::LOCKED_PROFIT_TRACKER),
+            last_report: read_u64(self.data, vault_offset::LOCKED_PROFIT_TRACKER + 8),
+            locked_profit_degradation: read_u64(
+                self.data,
+                vault_offset::LOCKED_PROFIT_TRACKER + 16,
+            ),
+        }
+    }
+
+    /// Get amount by share, same as `Vault::get_amount_by_share`
+    pub fn get_amount_by_share(
+        &self,
+        current_time: u64,
+        share: u64,
+        total_supply: u64,
+    ) -> Option<u64> {
+        self.math()
+            .get_amount_by_share(current_time, share, total_supply)
+    }
+
+    /// Get unmint amount by token amount, same as `Vault::get_unmint_amount`
+    pub fn get_unmint_amount(
+        &self,
+        current_time: u64,
+        out_token: u64,
+        total_supply: u64,
+    ) -> Option<u64> {
+        self.math()
+            .get_unmint_amount(current_time, out_token, total_supply)
+    }
+
+    fn math(&self) -> meteora_math::Vault {
+        meteora_math::Vault {
+            total_amount: self.total_amount(),
+            locked_profit_tracker: self.locked_profit_tracker().into(),
+        }
+    }
+}
+
+/// Accounts of a quote snapshot read through the views, to quote without decoding the pool and
+/// vault accounts
+#[derive(Debug, Clone)]
+pub struct QuoteViews<'a> {
+    pub pool: PoolView<'a>,
+    /// Swap curve of the pool
+    pub curve_type: CurveType,
+    pub vault_a: VaultView<'a>,
+    pub vault_b: VaultView<'a>,
+    /// Vault A LP held by the pool
+    pub pool_vault_a_lp_amount: u64,
+    /// Vault B LP held by the pool
+    pub pool_vault_b_lp_amount: u64,
+    /// Lp supply of mint of vault A
+    pub vault_a_lp_mint_supply: u64,
+    /// Lp supply of mint of vault B
+    pub vault_b_lp_mint_supply: u64,
+    /// Amount in the token account of vault A
+    pub vault_a_token_amount: u64,
+    /// Amount in the token account of vault B
+    pub vault_b_token_amount: u64,
+    /// Clock sysvar, read together with the accounts above
+    pub clock: Clock,
+}
+
+impl<'a> QuoteViews<'a> {
+    /// Read the snapshot accounts through the views. The accounts are checked against the pool
+    /// and vault states like `QuoteSnapshot::to_quote_data` does.
+    pub fn from_snapshot(snapshot: &'a QuoteSnapshot) -> anyhow::Result<Self> {
+        ensure!(
+            snapshot.clock.address == sysvar::clock::ID,
+            "Clock account is not the clock sysvar"
+        );
+        let clock = decode_clock(&snapshot.clock.data)?;
+        ensure!(
+            clock.slot == snapshot.slot,
+            "Snapshot slot does not match the clock"
+        );
+
+        let pool = PoolView::new(&snapshot.pool.data)?;
+        let curve_type = pool.curve_type()?;
+        let vault_a = VaultView::new(&snapshot.vault_a.data)?;
+        let vault_b = VaultView::new(&snapshot.vault_b.data)?;
+
+        for (name, account, expected) in [
+            ("Pool LP mint", &snapshot.pool_lp_mint, pool.lp_mint()),
+            ("Vault A", &snapshot.vault_a, pool.a_vault()),
+            ("Vault B", &snapshot.vault_b, pool.b_vault()),
+            (
+                "Vault A LP mint",
+                &snapshot.vault_a_lp_mint,
+                vault_a.lp_mint(),
+            ),
+            (
+                "Vault B LP mint",
+                &snapshot.vault_b_lp_mint,
+                vault_b.lp_mint(),
+            ),
+            (
+                "Pool vault A LP token",
+                &snapshot.pool_vault_a_lp_token,
+                pool.a_vault_lp(),
+            ),
+            (
+                "Pool vault B LP token",
+                &snapshot.pool_vault_b_lp_token,
+                pool.b_vault_lp(),
+            ),
+            (
+                "Vault A token",
+                &snapshot.vault_a_token,
+                vault_a.token_vault(),
+            ),
+            (
+                "Vault B token",
+                &snapshot.vault_b_token,
+                vault_b.token_vault(),
+            ),
+        ] {
+            ensure!(
+                account.address == expected,
+                "{} {} does not match the state, expected {}",
+                name,
+                account.address,
+                expected
+            );
+        }
+
+        Ok(Self {
+            pool,
+            curve_type,
+            vault_a,
+            vault_b,
+            pool_vault_a_lp_amount: decode_token_account(&snapshot.pool_vault_a_lp_token.data)?
+                .amount,
+            pool_vault_b_lp_amount: decode_token_account(&snapshot.pool_vault_b_lp_token.data)?
+                .amount,
+            vault_a_lp_mint_supply: decode_mint(&snapshot.vault_a_lp_mint.data)?.supply,
+            vault_b_lp_mint_supply: decode_mint(&snapshot.vault_b_lp_mint.data)?.supply,
+            vault_a_token_amount: decode_token_account(&snapshot.vault_a_token.data)?.amount,
+            vault_b_token_amount: decode_token_account(&snapshot.vault_b_token.data)?.amount,
+            clock,
+        })
+    }
+
+    /// Compute the quote like `compute_quote`, reading the pool and vaults through the views
+    pub fn compute_quote(
+        &self,
+        in_token_mint: Pubkey,
+        in_amount: u64,
+    ) -> anyhow::Result<QuoteResult> {
+        let pool = &self.pool;
+        ensure_swappable(
+            pool.enabled(),
+            &self.curve_type,
+            &pool.bootstrapping(),
+            &self.clock,
+            None,
+        )?;
+
+        let trade_direction =
+            get_trade_direction(in_token_mint, pool.token_a_mint(), pool.token_b_mint())?;
+
+        let SwapAmounts { quote_result, .. } = compute_swap(
+            &pool.fees(),
+            trade_direction,
+            in_amount,
+            &PoolSide {
+                vault: self.vault_a.math(),
+                pool_vault_lp_amount: self.pool_vault_a_lp_amount,
+                vault_lp_mint_supply: self.vault_a_lp_mint_supply,
+                vault_token_amount: self.vault_a_token_amount,
+            },
+            &PoolSide {
+                vault: self.vault_b.math(),
+                pool_vault_lp_amount: self.pool_vault_b_lp_amount,
+                vault_lp_mint_supply: self.vault_b_lp_mint_supply,
+                vault_token_amount: self.vault_b_token_amount,
+            },
+            &self.clock,
+        )?;
+
+        Ok(quote_result)
+    }
+}
+
+/// Check the anchor discriminator and return the account bytes up to the end of the fields
+fn account_data<'a, const N: usize>(
+    data: &'a [u8],
+    discriminator: [u8; 8],
+    name: &str,
+) -> anyhow::Result<&'a [u8; N]> {
+    ensure!(
+        data.get(..8) == Some(&discriminator[..]),
+        "Invalid {} discriminator",
+        name
+    );
+
+    data.get(..N)
+        .and_then(|data| data.try_into().ok())
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "{} account too short: {} bytes, expected at least {}",
+                name,
+                data.len(),
+                N
+            )
+        })
+}
+
+fn read_u64<const N: usize>(data: &[u8; N], offset: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&data[offset..offset + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+fn read_pubkey<const N: usize>(data: &[u8; N], offset: usize) -> Pubkey {
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&data[offset..offset + 32]);
+    Pubkey::new_from_array(bytes)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,204 @@
+use super::*;
+use crate::quote_service::compute_quote;
+use crate::test_fixtures::*;
+use proptest::collection::vec;
+use proptest::prelude::{any, prop_assert_eq, proptest};
+
+fn assert_pool_view_matches(data: &[u8]) {
+    let pool = decode_pool(data).unwrap();
+    let view = PoolView::new(data).unwrap();
+
+    assert_eq!(view.lp_mint(), pool.lp_mint);
+    assert_eq!(view.token_a_mint(), pool.token_a_mint);
+    assert_eq!(view.token_b_mint(), pool.token_b_mint);
+    assert_eq!(view.a_vault(), pool.a_vault);
+    assert_eq!(view.b_vault(), pool.b_vault);
+    assert_eq!(view.a_vault_lp(), pool.a_vault_lp);
+    assert_eq!(view.b_vault_lp(), pool.b_vault_lp);
+    assert_eq!(view.enabled(), pool.enabled);
+    assert_eq!(
+        meteora_math::PoolFees::from(view.fees()),
+        meteora_math::PoolFees::from(pool.fees)
+    );
+    assert_eq!(view.activation_point(), pool.bootstrapping.activation_point);
+    assert_eq!(
+        view.whitelisted_vault(),
+        pool.bootstrapping.whitelisted_vault
+    );
+    assert_eq!(view.activation_type(), pool.bootstrapping.activation_type);
+}
+
+fn assert_vault_view_matches(data: &[u8]) {
+    let vault = decode_vault(data).unwrap();
+    let view = VaultView::new(data).unwrap();
+
+    assert_eq!(view.enabled(), vault.enabled);
+    assert_eq!(view.total_amount(), vault.total_amount);
+    assert_eq!(view.token_vault(), vault.token_vault);
+    assert_eq!(view.token_mint(), vault.token_mint);
+    assert_eq!(view.lp_mint(), vault.lp_mint);
+    assert_eq!(
+        meteora_math::LockedProfitTracker::from(view.locked_profit_tracker()),
+        meteora_math::LockedProfitTracker::from(vault.locked_profit_tracker)
+    );
+}
+
+#[test]
+fn views_match_the_decoded_fixture_accounts() {
+    let snapshot = usdc_wsol_pool().snapshot();
+
+    assert_pool_view_matches(&snapshot.pool.data);
+    assert_vault_view_matches(&snapshot.vault_a.data);
+    assert_vault_view_matches(&snapshot.vault_b.data);
+}
+
+#[test]
+fn invalid_accounts_are_rejected() {
+    let snapshot = usdc_wsol_pool().snapshot();
+
+    assert!(PoolView::new(&snapshot.pool.data[..POOL_FIELDS_END - 1]).is_err());
+    assert!(VaultView::new(&snapshot.vault_a.data[..VAULT_FIELDS_END - 1]).is_err());
+    assert!(PoolView::new(&snapshot.vault_a.data).is_err());
+    assert!(VaultView::new(&snapshot.pool.data).is_err());
+    assert!(PoolView::new(&[]).is_err());
+
+    let mut unknown_pool_type = snapshot.pool.data.clone();
+    unknown_pool_type[pool_offset::POOL_TYPE] = 2;
+    assert!(decode_pool(&unknown_pool_type).is_err());
+    assert!(PoolView::new(&unknown_pool_type).is_err());
+}
+
+#[test]
+fn pool_view_reads_the_curve_type() {
+    let snapshot = usdc_wsol_pool().snapshot();
+    let view = PoolView::new(&snapshot.pool.data).unwrap();
+
+    assert!(matches!(
+        view.curve_type().unwrap(),
+        CurveType::ConstantProduct
+    ));
+    // The curve type lies after the pool fields the view checks
+    let pool_fields = &snapshot.pool.data[..POOL_FIELDS_END];
+    assert!(PoolView::new(pool_fields).unwrap().curve_type().is_err());
+}
+
+#[test]
+fn views_quote_like_the_decoded_accounts() {
+    for fixture in [usdc_wsol_pool(), metav_wsol_pool(), legacy_vault_pool()] {
+        let snapshot = fixture.snapshot();
+        let views = QuoteViews::from_snapshot(&snapshot).unwrap();
+        let quote_data = snapshot.to_quote_data().unwrap();
+
+        for in_token_mint in [fixture.vault_a.token_mint, fixture.vault_b.token_mint] {
+            for in_amount in [1, 1_000_000, 1_000_000_000, 1_000_000_000_000] {
+                assert_eq!(
+                    views.compute_quote(in_token_mint, in_amount).ok(),
+                    compute_quote(in_token_mint, in_amount, quote_data.clone()).ok()
+                );
+            }
+        }
+        assert!(views
+            .compute_quote(Pubkey::new_unique(), 1_000_000)
+            .is_err());
+    }
+}
+
+#[test]
+fn views_of_a_snapshot_with_swapped_accounts_are_rejected() {
+    let snapshot = usdc_wsol_pool().snapshot();
+    let snapshot = QuoteSnapshot {
+        vault_a_token: snapshot.vault_b_token.clone(),
+        ..snapshot
+    };
+
+    assert!(QuoteViews::from_snapshot(&snapshot).is_err());
+}
+
+proptest! {
+    #[test]
+    fn pool_view_matches_decoder(
+        fields in vec(any::<u8>(), POOL_FIELDS_END - 8),
+        enabled in any::<bool>(),
+        pool_type in 0u8..2,
+    ) {
+        let mut data = Pool::discriminator().to_vec();
+        data.extend(fields);
+        data[pool_offset::ENABLED] = enabled.into();
+        data[pool_offset::POOL_TYPE] = pool_type;
+
+        assert_pool_view_matches(&data);
+    }
+
+    #[test]
+    fn pool_view_accepts_the_pools_of_the_decoder(
+        fields in vec(any::<u8>(), POOL_FIELDS_END - 8),
+        enabled in any::<u8>(),
+        pool_type in any::<u8>(),
+    ) {
+        let mut data = Pool::discriminator().to_vec();
+        data.extend(fields);
+        data[pool_offset::ENABLED] = enabled;
+        data[pool_offset::POOL_TYPE] = pool_type;
+
+        prop_assert_eq!(PoolView::new(&data).is_ok(), decode_pool(&data).is_ok());
+    }
+
+    #[test]
+    fn vault_view_matches_decoder(fields in vec(any::<u8>(), VAULT_FIELDS_END - 8)) {
+        let mut data = Vault::discriminator().to_vec();
+        data.extend(fields);
+
+        assert_vault_view_matches(&data);
+    }
+
+    #[test]
+    fn vault_view_share_math_matches_vault(
+        fields in vec(any::<u8>(), VAULT_FIELDS_END - 8),
+        current_time in any::<u64>(),
+        amount in any::<u64>(),
+        total_supply in any::<u64>(),
+    ) {
+        let mut data = Vault::discriminator().to_vec();
+        data.extend(fields);
+        let vault = decode_vault(&data).unwrap();
+        let view = VaultView::new(&data).unwrap();
+
+        prop_assert_eq!(
+            view.get_amount_by_share(current_time, amount, total_supply),
+            vault.get_amount_by_share(current_time, amount, total_supply)
+        );
+        prop_assert_eq!(
+            view.get_unmint_amount(current_time, amount, total_supply),
+            vault.get_unmint_amount(current_time, amount, total_supply)
+        );
+    }
+
+    #[test]
+    fn views_never_panic(data in vec(any::<u8>(), 0..2048)) {
+        if let Ok(view) = PoolView::new(&data) {
+            let _ = (view.fees(), view.enabled(), view.activation_type(), view.curve_type());
+        }
+        if let Ok(view) = VaultView::new(&data) {
+            let _ = (view.locked_profit_tracker(), view.total_amount());
+        }
+    }
+
+    #[test]
+    fn views_quote_like_compute_quote(in_amount in any::<u64>(), a_to_b in any::<bool>()) {
+        let fixture = usdc_wsol_pool();
+        let snapshot = fixture.snapshot();
+        let in_token_mint = if a_to_b {
+            fixture.vault_a.token_mint
+        } else {
+            fixture.vault_b.token_mint
+        };
+
+        prop_assert_eq!(
+            QuoteViews::from_snapshot(&snapshot)
+                .unwrap()
+                .compute_quote(in_token_mint, in_amount)
+                .ok(),
+            compute_quote(in_token_mint, in_amount, snapshot.to_quote_data().unwrap()).ok()
+        );
+    }
+}
@@ -0,0 +1,192 @@
+use crate::quote_service::{apply_quote, sync_shared_vaults, QuoteData, QuoteResult};
+use crate::router::{Route, RouteHop};
+use solana_program::pubkey::Pubkey;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+/// Number of ternary search rounds used to find the optimal input of a cycle
+const MAX_SEARCH_ROUNDS: usize = 128;
+
+/// Profitable cycle starting and ending in the base token
+#[derive(Debug, Clone)]
+pub struct ArbitrageCycle {
+    /// Swaps of the cycle at the optimal input
+    pub route: Route,
+    /// Out amount minus in amount, in the base token
+    pub profit: u64,
+}
+
+/// Find the profitable cycles of at most `max_hops` swaps through the base token, with the
+/// optimal input of each cycle up to `max_in_amount`. Every hop is applied with `apply_quote` on
+/// the state left by the previous hops, so all fees, vault rounding and shared vaults are
+/// accounted for. The pools should be fetched together so that they form a consistent snapshot.
+///
+/// Cycles are sorted by profit, highest first.
+pub fn find_arbitrage_cycles(
+    base_mint: Pubkey,
+    pools: &[(Pubkey, QuoteData)],
+    max_hops: usize,
+    max_in_amount: u64,
+) -> Vec<ArbitrageCycle> {
+    let mut edges: HashMap<Pubkey, Vec<usize>> = HashMap::new();
+    for (index, (_, quote_data)) in pools.iter().enumerate() {
+        edges
+            .entry(quote_data.pool.token_a_mint)
+            .or_default()
+            .push(index);
+        edges
+            .entry(quote_data.pool.token_b_mint)
+            .or_default()
+            .push(index);
+    }
+
+    let mut cycles = vec![];
+    let mut path = vec![];
+    find_cycles(
+        base_mint,
+        base_mint,
+        pools,
+        &edges,
+        max_hops,
+        &mut path,
+        &mut cycles,
+    );
+
+    let mut arbitrage_cycles: Vec<ArbitrageCycle> = cycles
+        .iter()
+        .filter_map(|cycle| find_optimal_input(cycle, pools, max_in_amount))
+        .collect();
+    arbitrage_cycles.sort_by_key(|cycle| Reverse(cycle.profit));
+    arbitrage_cycles
+}
+
+/// Depth first search of the cycles through the base token. A step is the pool index and the
+/// mint swapped into it. Pools are used at most once per cycle.
+fn find_cycles(
+    base_mint: Pubkey,
+    mint: Pubkey,
+    pools: &[(Pubkey, QuoteData)],
+    edges: &HashMap<Pubkey, Vec<usize>>,
+    max_hops: usize,
+    path: &mut Vec<(usize, Pubkey)>,
+    cycles: &mut Vec<Vec<(usize, Pubkey)>>,
+) {
+    if path.len() == max_hops {
+        return;
+    }
+
+    for &index in edges.get(&mint).into_iter().flatten() {
+        if path.iter().any(|&(path_index, _)| path_index == index) {
+            continue;
+        }
+
+        let pool = &pools[index].1.pool;
+        let next_mint = if pool.token_a_mint == mint {
+            pool.token_b_mint
+        } else {
+            pool.token_a_mint
+        };
+
+        path.push((index, mint));
+        if next_mint == base_mint {
+            if path.len() >= 2 {
+                cycles.push(path.clone());
+            }
+        } else if path.iter().all(|&(_, path_mint)| path_mint != next_mint) {
+            find_cycles(base_mint, next_mint, pools, edges, max_hops, path, cycles);
+        }
+        path.pop();
+    }
+}
+
+/// Quote the cycle for `in_amount`, returning `None` when any hop can not be quoted. Each hop
+/// is applied to the state left by the previous ones, including the vaults shared between the
+/// pools of the cycle.
+fn quote_cycle(
+    cycle: &[(usize, Pubkey)],
+    pools: &[(Pubkey, QuoteData)],
+    in_amount: u64,
+) -> Option<Route> {
+    let mut states: Vec<QuoteData> = cycle
+        .iter()
+        .map(|&(index, _)| pools[index].1.clone())
+        .collect();
+    let mut hops = Vec::with_capacity(cycle.len());
+    let mut amount = in_amount;
+
+    for (position, &(index, in_token_mint)) in cycle.iter().enumerate() {
+        let pool_address = pools[index].0;
+        let quote_data = states[position].clone();
+        let out_token_mint = if quote_data.pool.token_a_mint == in_token_mint {
+            quote_data.pool.token_b_mint
+        } else {
+            quote_data.pool.token_a_mint
+        };
+        let (QuoteResult { out_amount, fee }, quote_data) =
+            apply_quote(in_token_mint, amount, quote_data).ok()?;
+        // Pools are used once per cycle, only the next hops see the swap
+        for next_quote_data in &mut states[position + 1..] {
+            sync_shared_vaults(&quote_data, next_quote_data);
+        }
+
+        hops.push(RouteHop {
+            pool: pool_address,
+            in_token_mint,
+            out_token_mint,
+            in_amount: amount,
+            out_amount,
+            fee,
+        });
+        amount = out_amount;
+    }
+
+    Some(Route {
+        hops,
+        in_amount,
+        out_amount: amount,
+    })
+}
+
+/// Ternary search of the input with the highest profit. The profit of a chain of constant
+/// product swaps is concave in the input, up to rounding.
+fn find_optimal_input(
+    cycle: &[(usize, Pubkey)],
+    pools: &[(Pubkey, QuoteData)],
+    max_in_amount: u64,
+) -> Option<ArbitrageCycle> {
+    let profit = |in_amount: u64| -> i128 {
+        quote_cycle(cycle, pools, in_amount).map_or(i128::MIN, |route| {
+            i128::from(route.out_amount) - i128::from(in_amount)
+        })
+    };
+
+    let (mut low, mut high) = (1u64, max_in_amount);
+    for _ in 0..MAX_SEARCH_ROUNDS {
+        if high.saturating_sub(low) <= 2 {
+            break;
+        }
+        let third = (high - low) / 3;
+        let (middle_low, middle_high) = (low + third, high - third);
+        if profit(middle_low) < profit(middle_high) {
+            low = middle_low;
+        } else {
+            high = middle_high;
+        }
+    }
+
+    // Rounding makes the profit flat over small steps, so the search can stop short of the cap
+    // while the profit still grows there
+    let in_amount = (low..=high)
+        .chain([max_in_amount])
+        .max_by_key(|&in_amount| profit(in_amount))?;
+    let route = quote_cycle(cycle, pools, in_amount)?;
+    let profit = route.out_amount.checked_sub(route.in_amount)?;
+    if profit == 0 {
+        return None;
+    }
+
+    Some(ArbitrageCycle { route, profit })
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,196 @@
+use super::*;
+use crate::quote_ladder::geometric_amounts;
+use crate::quote_service::compute_quote;
+use crate::test_fixtures::*;
+
+const MAX_IN_AMOUNT: u64 = 10_000_000_000_000;
+
+fn candidate(fixture: &PoolFixture) -> (Pubkey, QuoteData) {
+    (
+        fixture.pool_address,
+        fixture.snapshot().to_quote_data().unwrap(),
+    )
+}
+
+/// USDC/WSOL pool sharing the vaults of `usdc_wsol_pool`, with WSOL 5% cheaper
+fn cheap_wsol_pool() -> PoolFixture {
+    PoolFixture {
+        pool_address: Pubkey::new_unique(),
+        vault_b: wsol_vault(7_102_443_118_004 / 100 * 105),
+        ..usdc_wsol_pool()
+    }
+}
+
+fn hop_pools(route: &Route) -> Vec<Pubkey> {
+    route.hops.iter().map(|hop| hop.pool).collect()
+}
+
+fn profit(cycle: &[(usize, Pubkey)], pools: &[(Pubkey, QuoteData)], in_amount: u64) -> i128 {
+    quote_cycle(cycle, pools, in_amount).map_or(i128::MIN, |route| {
+        i128::from(route.out_amount) - i128::from(in_amount)
+    })
+}
+
+#[test]
+fn price_gap_between_two_pools_is_found() {
+    let cheap_pool = cheap_wsol_pool();
+    let pools = [candidate(&usdc_wsol_pool()), candidate(&cheap_pool)];
+
+    let cycles = find_arbitrage_cycles(WSOL_MINT, &pools, 2, MAX_IN_AMOUNT);
+
+    // WSOL is sold where it is expensive and bought back where it is cheap, the reverse cycle
+    // loses money
+    assert_eq!(cycles.len(), 1);
+    let ArbitrageCycle { route, profit } = &cycles[0];
+    assert_eq!(
+        hop_pools(route),
+        vec![USDC_WSOL_POOL, cheap_pool.pool_address]
+    );
+    assert_eq!(route.hops[0].in_token_mint, WSOL_MINT);
+    assert_eq!(route.hops[1].out_token_mint, WSOL_MINT);
+    assert_eq!(route.hops[0].out_amount, route.hops[1].in_amount);
+    assert_eq!(*profit, route.out_amount - route.in_amount);
+    assert!(*profit > 0);
+}
+
+#[test]
+fn pools_at_the_same_price_have_no_cycle() {
+    let same_price_pool = PoolFixture {
+        pool_address: Pubkey::new_unique(),
+        ..usdc_wsol_pool()
+    };
+    let pools = [candidate(&usdc_wsol_pool()), candidate(&same_price_pool)];
+
+    assert!(find_arbitrage_cycles(WSOL_MINT, &pools, 2, MAX_IN_AMOUNT).is_empty());
+}
+
+#[test]
+fn cycles_are_bounded_by_max_hops() {
+    let [x_mint, y_mint, z_mint] = [(); 3].map(|_| Pubkey::new_unique());
+    let amount = 1_000_000_000_000;
+    // Z is 5% cheaper in the Z-X pool than through Y, so X buys Z there and sells it through Y
+    let pools = [
+        candidate(&token_pool(
+            Pubkey::new_unique(),
+            (x_mint, amount),
+            (y_mint, amount),
+        )),
+        candidate(&token_pool(
+            Pubkey::new_unique(),
+            (y_mint, amount),
+            (z_mint, amount),
+        )),
+        candidate(&token_pool(
+            Pubkey::new_unique(),
+            (z_mint, amount / 100 * 105),
+            (x_mint, amount),
+        )),
+    ];
+
+    assert!(find_arbitrage_cycles(x_mint, &pools, 2, MAX_IN_AMOUNT).is_empty());
+
+    let cycles = find_arbitrage_cycles(x_mint, &pools, 3, MAX_IN_AMOUNT);
+    assert_eq!(cycles.len(), 1);
+    assert_eq!(
+        hop_pools(&cycles[0].route),
+        vec![pools[2].0, pools[1].0, pools[0].0]
+    );
+}
+
+#[test]
+fn cycles_use_each_pool_once() {
+    let pools = [candidate(&usdc_wsol_pool()), candidate(&cheap_wsol_pool())];
+    let mut edges: HashMap<Pubkey, Vec<usize>> = HashMap::new();
+    for (index, (_, quote_data)) in pools.iter().enumerate() {
+        edges
+            .entry(quote_data.pool.token_a_mint)
+            .or_default()
+            .push(index);
+        edges
+            .entry(quote_data.pool.token_b_mint)
+            .or_default()
+            .push(index);
+    }
+
+    let mut cycles = vec![];
+    find_cycles(
+        WSOL_MINT,
+        WSOL_MINT,
+        &pools,
+        &edges,
+        4,
+        &mut vec![],
+        &mut cycles,
+    );
+
+    assert_eq!(
+        cycles,
+        vec![
+            vec![(0, WSOL_MINT), (1, USDC_MINT)],
+            vec![(1, WSOL_MINT), (0, USDC_MINT)],
+        ]
+    );
+}
+
+#[test]
+fn cycle_hops_carry_the_shared_vault_state() {
+    let pools = [candidate(&usdc_wsol_pool()), candidate(&cheap_wsol_pool())];
+    let cycle = [(0, WSOL_MINT), (1, USDC_MINT)];
+    let in_amount = 50_000_000_000;
+
+    let route = quote_cycle(&cycle, &pools, in_amount).unwrap();
+
+    // Both pools deposit into the same USDC and WSOL vaults, so the second hop is quoted on the
+    // vaults left by the first one
+    let (first, first_quote_data) = apply_quote(WSOL_MINT, in_amount, pools[0].1.clone()).unwrap();
+    let mut second_quote_data = pools[1].1.clone();
+    sync_shared_vaults(&first_quote_data, &mut second_quote_data);
+    let second = compute_quote(USDC_MINT, first.out_amount, second_quote_data).unwrap();
+    assert_eq!(route.hops[0].out_amount, first.out_amount);
+    assert_eq!(route.out_amount, second.out_amount);
+}
+
+#[test]
+fn optimal_input_beats_every_other_input() {
+    let pools = [candidate(&usdc_wsol_pool()), candidate(&cheap_wsol_pool())];
+    let cycle = [(0, WSOL_MINT), (1, USDC_MINT)];
+
+    let ArbitrageCycle { route, profit } =
+        find_optimal_input(&cycle, &pools, MAX_IN_AMOUNT).unwrap();
+
+    // The optimum is inside the range, and no input of a fine grid does better
+    assert!(route.in_amount > 1 && route.in_amount < MAX_IN_AMOUNT);
+    let best_on_grid = geometric_amounts(1_000, MAX_IN_AMOUNT, 1_000)
+        .into_iter()
+        .map(|in_amount| super::tests::profit(&cycle, &pools, in_amount))
+        .max()
+        .unwrap();
+    assert!(i128::from(profit) >= best_on_grid);
+    for in_amount in [route.in_amount - 1_000_000, route.in_amount + 1_000_000] {
+        assert!(i128::from(profit) >= super::tests::profit(&cycle, &pools, in_amount));
+    }
+}
+
+#[test]
+fn optimal_input_is_capped_by_max_in_amount() {
+    let pools = [candidate(&usdc_wsol_pool()), candidate(&cheap_wsol_pool())];
+    let cycle = [(0, WSOL_MINT), (1, USDC_MINT)];
+    let max_in_amount = 1_000_000_000;
+
+    let ArbitrageCycle { route, profit } =
+        find_optimal_input(&cycle, &pools, max_in_amount).unwrap();
+
+    // Profit still grows at the cap
+    assert_eq!(route.in_amount, max_in_amount);
+    assert_eq!(
+        i128::from(profit),
+        super::tests::profit(&cycle, &pools, max_in_amount)
+    );
+}
+
+#[test]
+fn unprofitable_cycle_has_no_optimal_input() {
+    let pools = [candidate(&usdc_wsol_pool()), candidate(&cheap_wsol_pool())];
+
+    assert!(find_optimal_input(&[(1, WSOL_MINT), (0, USDC_MINT)], &pools, MAX_IN_AMOUNT).is_none());
+}
@@ -0,0 +1,76 @@
+use blaze_meteora_test_task::constant::RPC_URL;
+use blaze_meteora_test_task::pda_resolver::PdaResolver;
+use blaze_meteora_test_task::quote_service::QuoteService;
+use blaze_meteora_test_task::rpc_service::RpcService;
+use blaze_meteora_test_task::server;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::signal::unix::{signal, SignalKind};
+
+/// Address the server binds to, `127.0.0.1:8080` when unset
+const BIND_ADDRESS_ENV: &str = "QUOTE_SERVER_ADDRESS";
+/// File the PDA cache is loaded from on start and saved to on shutdown, not persisted when unset
+const PDA_CACHE_PATH_ENV: &str = "QUOTE_SERVER_PDA_CACHE";
+/// How often the PDA cache is saved while serving, so a killed server loses at most this much
+const PDA_CACHE_SAVE_INTERVAL: Duration = Duration::from_secs(300);
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let address: SocketAddr = std::env::var(BIND_ADDRESS_ENV)
+        .unwrap_or_else(|_| "127.0.0.1:8080".to_string())
+        .parse()?;
+    let pda_cache_path = std::env::var_os(PDA_CACHE_PATH_ENV);
+
+    let pda_resolver = match &pda_cache_path {
+        Some(path) => PdaResolver::load_or_default(path)?,
+        None => PdaResolver::new(),
+    };
+    println!("Loaded {} pools in the PDA cache", pda_resolver.len());
+
+    let quote_service = Arc::new(
+        QuoteService::new(RpcService::new(RPC_URL.to_string())).with_pda_resolver(pda_resolver),
+    );
+
+    let periodic_save = pda_cache_path.clone().map(|path| {
+        let quote_service = quote_service.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(PDA_CACHE_SAVE_INTERVAL);
+            // The first tick completes at once, the cache was just loaded
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                if let Err(err) = quote_service.pda_resolver().save(&path) {
+                    eprintln!("{:#}", err);
+                }
+            }
+        })
+    });
+
+    // Stop on Ctrl-C, and on the SIGTERM of `docker stop`, systemd or Kubernetes
+    let mut sigterm = signal(SignalKind::terminate())?;
+    let (address, server) = warp::serve(server::routes(quote_service.clone()))
+        .bind_with_graceful_shutdown(address, async move {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = sigterm.recv() => {}
+            }
+        });
+    println!("Quote server listening on {}", address);
+    server.await;
+
+    if let Some(periodic_save) = periodic_save {
+        // Wait for a save in progress, both write the same file
+        periodic_save.abort();
+        periodic_save.await.ok();
+    }
+    if let Some(path) = &pda_cache_path {
+        quote_service.pda_resolver().save(path)?;
+        println!(
+            "Saved {} pools in the PDA cache",
+            quote_service.pda_resolver().len()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use crate::state::*;
+use anchor_lang::{AccountDeserialize, AnchorDeserialize};
+use anchor_spl::token::{Mint, TokenAccount};
+use anyhow::{ensure, Context};
+use solana_program::clock::Clock;
+
+/// End of the pool fields decoded by `Pool`
+pub const POOL_FIELDS_END: usize = 532;
+/// Offset of the curve type in the pool account
+pub const POOL_CURVE_TYPE_OFFSET: usize = 874;
+/// End of the vault fields decoded by `Vault`
+pub const VAULT_FIELDS_END: usize = 1227;
+/// Size of the clock sysvar account
+pub const CLOCK_ACCOUNT_SIZE: usize = 40;
+
+/// Check the anchor discriminator and return the account fields after it, up to `end` when set
+fn account_fields<'a>(
+    data: &'a [u8],
+    discriminator: [u8; 8],
+    end: Option<usize>,
+    name: &str,
+) -> anyhow::Result<&'a [u8]> {
+    ensure!(
+        data.get(..8) == Some(&discriminator[..]),
+        "Invalid {} discriminator",
+        name
+    );
+    let end = end.unwrap_or(data.len());
+
+    data.get(8..end).with_context(|| {
+        format!(
+            "{} account too short: {} bytes, expected at least {}",
+            name,
+            data.len(),
+            end
+        )
+    })
+}
+
+/// Decode a pool account
+pub fn decode_pool(data: &[u8]) -> anyhow::Result<Pool> {
+    let fields = account_fields(data, Pool::discriminator(), Some(POOL_FIELDS_END), "pool")?;
+
+    Pool::try_from_slice(fields).context("Fail to deserialize pool")
+}
+
+/// Decode the swap curve of a pool account
+pub fn decode_curve_type(data: &[u8]) -> anyhow::Result<CurveType> {
+    let mut fields = data
+        .get(POOL_CURVE_TYPE_OFFSET..)
+        .context("Pool account too short for the curve type")?;
+
+    CurveType::deserialize(&mut fields).context("Fail to deserialize curve type")
+}
+
+/// Decode a vault account
+pub fn decode_vault(data: &[u8]) -> anyhow::Result<Vault> {
+    let fields = account_fields(
+        data,
+        Vault::discriminator(),
+        Some(VAULT_FIELDS_END),
+        "vault",
+    )?;
+
+    Vault::try_from_slice(fields).context("Fail to deserialize vault")
+}
+
+/// Decode a vault strategy account
+pub fn decode_strategy(data: &[u8]) -> anyhow::Result<Strategy> {
+    let mut fields = account_fields(data, Strategy::discriminator(), None, "strategy")?;
+
+    Strategy::deserialize(&mut fields).context("Fail to deserialize strategy")
+}
+
+/// Decode a config account
+pub fn decode_config(data: &[u8]) -> anyhow::Result<Config> {
+    let fields = account_fields(
+        data,
+        Config::discriminator(),
+        Some(CONFIG_ACCOUNT_SIZE),
+        "config",
+    )?;
+
+    Config::try_from_slice(fields).context("Fail to deserialize config")
+}
+
+/// Decode a lock escrow account
+pub fn decode_lock_escrow(data: &[u8]) -> anyhow::Result<LockEscrow> {
+    let mut fields = account_fields(data, LockEscrow::discriminator(), None, "lock escrow")?;
+
+    LockEscrow::deserialize(&mut fields).context("Fail to deserialize lock escrow")
+}
+
+/// Decode an SPL token mint account
+pub fn decode_mint(data: &[u8]) -> anyhow::Result<Mint> {
+    Mint::try_deserialize_unchecked(&mut &data[..]).context("Fail to deserialize mint")
+}
+
+/// Decode an SPL token account
+pub fn decode_token_account(data: &[u8]) -> anyhow::Result<TokenAccount> {
+    TokenAccount::try_deserialize_unchecked(&mut &data[..])
+        .context("Fail to deserialize token account")
+}
+
+/// Decode the clock sysvar account
+pub fn decode_clock(data: &[u8]) -> anyhow::Result<Clock> {
+    ensure!(
+        data.len() == CLOCK_ACCOUNT_SIZE,
+        "Invalid clock account size: {} bytes",
+        data.len()
+    );
+
+    bincode::deserialize(data).context("Fail to deserialize clock")
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,75 @@
+use super::*;
+use crate::test_fixtures::usdc_wsol_pool;
+use anchor_lang::AnchorSerialize;
+use proptest::collection::vec;
+use proptest::prelude::{any, proptest};
+use solana_program::pubkey::Pubkey;
+
+#[test]
+fn short_accounts_are_rejected() {
+    let snapshot = usdc_wsol_pool().snapshot();
+
+    assert!(decode_pool(&snapshot.pool.data[..POOL_FIELDS_END - 1]).is_err());
+    assert!(decode_curve_type(&snapshot.pool.data[..POOL_CURVE_TYPE_OFFSET]).is_err());
+    assert!(decode_vault(&snapshot.vault_a.data[..VAULT_FIELDS_END - 1]).is_err());
+    assert!(decode_mint(&snapshot.vault_a_lp_mint.data[1..]).is_err());
+    assert!(decode_token_account(&snapshot.vault_a_token.data[1..]).is_err());
+    assert!(decode_clock(&snapshot.clock.data[1..]).is_err());
+    assert!(decode_pool(&[]).is_err());
+    assert!(decode_vault(&[]).is_err());
+}
+
+#[test]
+fn accounts_of_another_type_are_rejected() {
+    let snapshot = usdc_wsol_pool().snapshot();
+
+    assert!(decode_pool(&snapshot.vault_a.data).is_err());
+    assert!(decode_vault(&snapshot.pool.data).is_err());
+    assert!(decode_strategy(&snapshot.vault_a.data).is_err());
+}
+
+#[test]
+fn strategy_round_trip() {
+    let strategy = Strategy {
+        reserve: Pubkey::new_unique(),
+        collateral_vault: Pubkey::new_unique(),
+        strategy_type: StrategyType::Kamino,
+        current_liquidity: 1_885_494_083_114,
+        bumps: [7; MAX_BUMPS],
+        vault: Pubkey::new_unique(),
+        is_disable: 0,
+    };
+    let mut data = Strategy::discriminator().to_vec();
+    strategy.serialize(&mut data).unwrap();
+
+    let decoded = decode_strategy(&data).unwrap();
+    assert_eq!(decoded.reserve, strategy.reserve);
+    assert_eq!(decoded.strategy_type, strategy.strategy_type);
+    assert_eq!(decoded.current_liquidity, strategy.current_liquidity);
+    assert_eq!(decoded.vault, strategy.vault);
+    assert!(decode_strategy(&data[..data.len() - 1]).is_err());
+}
+
+proptest! {
+    #[test]
+    fn decoders_never_panic(data in vec(any::<u8>(), 0..2048)) {
+        let _ = decode_pool(&data);
+        let _ = decode_curve_type(&data);
+        let _ = decode_vault(&data);
+        let _ = decode_config(&data);
+        let _ = decode_lock_escrow(&data);
+        let _ = decode_strategy(&data);
+        let _ = decode_mint(&data);
+        let _ = decode_token_account(&data);
+        let _ = decode_clock(&data);
+    }
+
+    #[test]
+    fn decoders_never_panic_behind_discriminator(data in vec(any::<u8>(), 0..2048)) {
+        let _ = decode_pool(&[&Pool::discriminator()[..], &data].concat());
+        let _ = decode_vault(&[&Vault::discriminator()[..], &data].concat());
+        let _ = decode_config(&[&Config::discriminator()[..], &data].concat());
+        let _ = decode_lock_escrow(&[&LockEscrow::discriminator()[..], &data].concat());
+        let _ = decode_strategy(&[&Strategy::discriminator()[..], &data].concat());
+    }
+}
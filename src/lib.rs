@@ -0,0 +1,33 @@
+//! Quoting for Meteora AMM volatile pools.
+//!
+//! The state types, swap curve and vault math build without any network dependency.
+//! The `rpc` feature (default) adds the RPC client and `QuoteService`, and the `server`
+//! feature adds the HTTP layer in `server`.
+
+pub mod account_view;
+pub mod arbitrage;
+pub mod constant;
+pub mod decoder;
+pub mod lp_analytics;
+pub mod lp_position;
+pub mod order_split;
+pub mod pda_resolver;
+pub mod pool_validation;
+pub mod quote_ladder;
+pub mod quote_service;
+pub mod router;
+#[cfg(feature = "rpc")]
+pub mod rpc_service;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod snapshot;
+pub mod state;
+#[cfg(test)]
+pub mod test_fixtures;
+pub mod vault_analytics;
+pub mod vault_quote;
+pub mod vault_solvency;
+
+#[cfg(feature = "rpc")]
+pub use quote_service::QuoteService;
+pub use quote_service::{compute_quote, compute_quote_for_user, QuoteData, QuoteResult};
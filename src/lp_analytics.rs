@@ -0,0 +1,122 @@
+use crate::lp_position::get_lp_token_amounts;
+use crate::quote_service::{get_pool_reserves, QuoteData};
+use anyhow::ensure;
+
+/// Pool state and pool LP supply at a point in time, see `QuoteSnapshot::to_pool_lp_snapshot`
+#[derive(Clone)]
+pub struct PoolLpSnapshot {
+    /// Pool, vaults and clock
+    pub quote_data: QuoteData,
+    /// Supply of the pool LP mint
+    pub pool_lp_supply: u64,
+}
+
+/// Performance of a pool LP position between two snapshots. Values are in token B.
+#[derive(Debug, Clone)]
+pub struct LpPerformance {
+    /// Token A amount of the position at entry
+    pub entry_token_a_amount: u64,
+    /// Token B amount of the position at entry
+    pub entry_token_b_amount: u64,
+    /// Token A amount of the position now
+    pub current_token_a_amount: u64,
+    /// Token B amount of the position now
+    pub current_token_b_amount: u64,
+    /// Current price of token A, in token B, from the pool reserves
+    pub price: f64,
+    /// Current value of the position
+    pub value: f64,
+    /// Current value of the entry tokens, had they been held instead
+    pub hodl_value: f64,
+    /// Value without fees relative to the HODL value, minus one. Zero or negative.
+    pub impermanent_loss: f64,
+    /// Growth of the LP virtual price, sqrt(reserve_a * reserve_b) / lp_supply, between the
+    /// snapshots. It includes the lending yield of the vaults.
+    pub virtual_price_growth: f64,
+    /// Part of the current value earned from the virtual price growth
+    pub fee_earnings: f64,
+    /// Pool volume, in token B, needed to earn the pool-wide virtual price growth at the LP fee
+    /// rate of the pool
+    pub implied_volume: f64,
+}
+
+/// Compute the impermanent loss and fee earnings of `lp_amount` of pool LP held from the entry
+/// snapshot to the current snapshot
+pub fn compute_lp_performance(
+    lp_amount: u64,
+    entry: &PoolLpSnapshot,
+    current: &PoolLpSnapshot,
+) -> anyhow::Result<LpPerformance> {
+    let current_pool = &current.quote_data.pool;
+    // The LP mint is a PDA of the pool, unlike the token mints it tells pools of a pair apart
+    ensure!(
+        entry.quote_data.pool.lp_mint == current_pool.lp_mint,
+        "Snapshots are not of the same pool"
+    );
+
+    let (entry_token_a_amount, entry_token_b_amount) =
+        get_lp_token_amounts(lp_amount, entry.pool_lp_supply, &entry.quote_data)?;
+    let (current_token_a_amount, current_token_b_amount) =
+        get_lp_token_amounts(lp_amount, current.pool_lp_supply, &current.quote_data)?;
+
+    let (entry_reserve_a, entry_reserve_b) = get_pool_reserves(&entry.quote_data)?;
+    let (current_reserve_a, current_reserve_b) = get_pool_reserves(&current.quote_data)?;
+    ensure!(current_reserve_a > 0, "Pool token A reserve is empty");
+
+    let price = current_reserve_b as f64 / current_reserve_a as f64;
+
+    let value = current_token_b_amount as f64 + current_token_a_amount as f64 * price;
+    let hodl_value = entry_token_b_amount as f64 + entry_token_a_amount as f64 * price;
+    ensure!(hodl_value > 0.0, "Position is empty");
+
+    let virtual_price = |reserve_a: u64, reserve_b: u64, lp_supply: u64| -> f64 {
+        (reserve_a as f64 * reserve_b as f64).sqrt() / lp_supply as f64
+    };
+    let entry_virtual_price = virtual_price(entry_reserve_a, entry_reserve_b, entry.pool_lp_supply);
+    let current_virtual_price =
+        virtual_price(current_reserve_a, current_reserve_b, current.pool_lp_supply);
+    ensure!(entry_virtual_price > 0.0, "Entry virtual price is zero");
+    let virtual_price_growth = current_virtual_price / entry_virtual_price;
+
+    // Without the virtual price growth, the position would be worth value / growth
+    let value_without_fees = value / virtual_price_growth;
+    let fee_earnings = value - value_without_fees;
+    let impermanent_loss = value_without_fees / hodl_value - 1.0;
+
+    let fees = &current_pool.fees;
+    let trade_fee_rate = if fees.trade_fee_denominator == 0 {
+        0.0
+    } else {
+        fees.trade_fee_numerator as f64 / fees.trade_fee_denominator as f64
+    };
+    let protocol_fee_share = if fees.protocol_trade_fee_denominator == 0 {
+        0.0
+    } else {
+        fees.protocol_trade_fee_numerator as f64 / fees.protocol_trade_fee_denominator as f64
+    };
+    let lp_fee_rate = trade_fee_rate * (1.0 - protocol_fee_share);
+    let pool_value = current_reserve_b as f64 + current_reserve_a as f64 * price;
+    let pool_fee_earnings = pool_value - pool_value / virtual_price_growth;
+    let implied_volume = if lp_fee_rate > 0.0 {
+        pool_fee_earnings / lp_fee_rate
+    } else {
+        0.0
+    };
+
+    Ok(LpPerformance {
+        entry_token_a_amount,
+        entry_token_b_amount,
+        current_token_a_amount,
+        current_token_b_amount,
+        price,
+        value,
+        hodl_value,
+        impermanent_loss,
+        virtual_price_growth,
+        fee_earnings,
+        implied_volume,
+    })
+}
+
+#[cfg(test)]
+mod tests;
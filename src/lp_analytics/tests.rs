@@ -0,0 +1,117 @@
+use super::*;
+use crate::quote_service::apply_quote;
+use crate::snapshot::QuoteSnapshot;
+use crate::test_fixtures::*;
+use solana_program::pubkey::Pubkey;
+
+/// A tenth of the pool LP
+const LP_AMOUNT: u64 = POOL_LP_SUPPLY / 10;
+
+fn usdc_wsol_lp_snapshot() -> PoolLpSnapshot {
+    usdc_wsol_pool().snapshot().to_pool_lp_snapshot().unwrap()
+}
+
+/// Pool LP snapshot after swapping `in_amount` of each mint in turn
+fn after_swaps(entry: &PoolLpSnapshot, swaps: &[(Pubkey, u64)]) -> PoolLpSnapshot {
+    let quote_data = swaps.iter().fold(
+        entry.quote_data.clone(),
+        |quote_data, &(in_token_mint, in_amount)| {
+            apply_quote(in_token_mint, in_amount, quote_data).unwrap().1
+        },
+    );
+
+    PoolLpSnapshot {
+        quote_data,
+        ..entry.clone()
+    }
+}
+
+#[test]
+fn pool_lp_snapshot_is_rebuilt_from_a_saved_quote_snapshot() {
+    let json = usdc_wsol_pool().snapshot().to_json().unwrap();
+
+    let snapshot = QuoteSnapshot::from_json(&json)
+        .unwrap()
+        .to_pool_lp_snapshot()
+        .unwrap();
+
+    assert_eq!(snapshot.pool_lp_supply, POOL_LP_SUPPLY);
+    let performance =
+        compute_lp_performance(LP_AMOUNT, &snapshot, &usdc_wsol_lp_snapshot()).unwrap();
+    assert_eq!(performance.virtual_price_growth, 1.0);
+}
+
+#[test]
+fn unchanged_pool_has_no_loss_and_no_earnings() {
+    let snapshot = usdc_wsol_lp_snapshot();
+
+    let performance = compute_lp_performance(LP_AMOUNT, &snapshot, &snapshot).unwrap();
+
+    assert_eq!(
+        performance.entry_token_a_amount,
+        performance.current_token_a_amount
+    );
+    assert_eq!(
+        performance.entry_token_b_amount,
+        performance.current_token_b_amount
+    );
+    assert_eq!(performance.value, performance.hodl_value);
+    assert_eq!(performance.impermanent_loss, 0.0);
+    assert_eq!(performance.fee_earnings, 0.0);
+    assert_eq!(performance.implied_volume, 0.0);
+}
+
+#[test]
+fn round_trip_trades_earn_fees() {
+    let entry = usdc_wsol_lp_snapshot();
+    let (wsol_in_amount, usdc_in_amount) = (100_000_000_000, 10_000_000_000);
+    let current = after_swaps(
+        &entry,
+        &[(WSOL_MINT, wsol_in_amount), (USDC_MINT, usdc_in_amount)],
+    );
+
+    let performance = compute_lp_performance(LP_AMOUNT, &entry, &current).unwrap();
+
+    assert!(performance.virtual_price_growth > 1.0);
+    assert!(performance.fee_earnings > 0.0);
+    // The fees kept by the pool are those of the traded volume, in WSOL
+    let volume = wsol_in_amount as f64 + usdc_in_amount as f64 * performance.price;
+    assert!(
+        (performance.implied_volume / volume - 1.0).abs() < 0.01,
+        "{} is not close to {}",
+        performance.implied_volume,
+        volume
+    );
+}
+
+#[test]
+fn one_way_trade_has_impermanent_loss() {
+    let entry = usdc_wsol_lp_snapshot();
+    let current = after_swaps(&entry, &[(WSOL_MINT, 1_000_000_000_000)]);
+
+    let performance = compute_lp_performance(LP_AMOUNT, &entry, &current).unwrap();
+
+    // The swap took USDC out of the pool for WSOL, so the position holds less USDC and more WSOL
+    assert!(performance.current_token_a_amount < performance.entry_token_a_amount);
+    assert!(performance.current_token_b_amount > performance.entry_token_b_amount);
+    assert!(performance.impermanent_loss < 0.0);
+    assert!(performance.fee_earnings > 0.0);
+}
+
+#[test]
+fn snapshots_of_another_pool_of_the_pair_are_rejected() {
+    let other_pool = PoolFixture {
+        pool_address: Pubkey::new_unique(),
+        ..usdc_wsol_pool()
+    };
+    let other_snapshot = other_pool.snapshot().to_pool_lp_snapshot().unwrap();
+
+    assert!(compute_lp_performance(LP_AMOUNT, &usdc_wsol_lp_snapshot(), &other_snapshot).is_err());
+}
+
+#[test]
+fn empty_position_is_rejected() {
+    let snapshot = usdc_wsol_lp_snapshot();
+
+    assert!(compute_lp_performance(0, &snapshot, &snapshot).is_err());
+}
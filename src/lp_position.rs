@@ -0,0 +1,77 @@
+use crate::quote_service::{get_pool_reserves, QuoteData};
+use anyhow::{ensure, Context};
+use solana_program::pubkey::Pubkey;
+
+/// Where the pool LP of a position is held
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LpPositionSource {
+    /// Pool LP token account owned by the wallet
+    Wallet { token_account: Pubkey },
+    /// Pool LP locked in a lock escrow owned by the wallet, counted in `Pool.total_locked_lp`
+    LockEscrow { lock_escrow: Pubkey },
+}
+
+/// Pool LP position of a wallet, valued in both tokens of the pool
+#[derive(Debug, Clone)]
+pub struct LpPosition {
+    /// Pool address
+    pub pool: Pubkey,
+    /// Where the pool LP is held
+    pub source: LpPositionSource,
+    /// Pool LP amount of the position
+    pub lp_amount: u64,
+    /// Token A amount the position can be withdrawn for
+    pub token_a_amount: u64,
+    /// Token B amount the position can be withdrawn for
+    pub token_b_amount: u64,
+}
+
+/// Get the amounts of token A and token B that `lp_amount` of pool LP can be withdrawn for. The
+/// pool reserves are computed with the same vault share math as the quote.
+pub fn get_lp_token_amounts(
+    lp_amount: u64,
+    pool_lp_supply: u64,
+    quote_data: &QuoteData,
+) -> anyhow::Result<(u64, u64)> {
+    ensure!(pool_lp_supply > 0, "Pool has no LP supply");
+    ensure!(lp_amount <= pool_lp_supply, "LP amount > pool LP supply");
+
+    let (token_a_amount, token_b_amount) = get_pool_reserves(quote_data)?;
+
+    let share = |reserve: u64| -> Option<u64> {
+        u64::try_from(
+            u128::from(lp_amount)
+                .checked_mul(u128::from(reserve))?
+                .checked_div(u128::from(pool_lp_supply))?,
+        )
+        .ok()
+    };
+
+    Ok((
+        share(token_a_amount).context("Fail to get token a amount")?,
+        share(token_b_amount).context("Fail to get token b amount")?,
+    ))
+}
+
+/// Value the pool LP position in both tokens of the pool
+pub fn value_lp_position(
+    pool: Pubkey,
+    source: LpPositionSource,
+    lp_amount: u64,
+    pool_lp_supply: u64,
+    quote_data: &QuoteData,
+) -> anyhow::Result<LpPosition> {
+    let (token_a_amount, token_b_amount) =
+        get_lp_token_amounts(lp_amount, pool_lp_supply, quote_data)?;
+
+    Ok(LpPosition {
+        pool,
+        source,
+        lp_amount,
+        token_a_amount,
+        token_b_amount,
+    })
+}
+
+#[cfg(test)]
+mod tests;
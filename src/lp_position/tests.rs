@@ -0,0 +1,72 @@
+use super::*;
+use crate::test_fixtures::*;
+
+fn usdc_wsol_quote_data() -> QuoteData {
+    usdc_wsol_pool().snapshot().to_quote_data().unwrap()
+}
+
+#[test]
+fn whole_lp_supply_withdraws_the_pool_reserves() {
+    let quote_data = usdc_wsol_quote_data();
+
+    assert_eq!(
+        get_lp_token_amounts(POOL_LP_SUPPLY, POOL_LP_SUPPLY, &quote_data).unwrap(),
+        get_pool_reserves(&quote_data).unwrap()
+    );
+}
+
+#[test]
+fn lp_share_is_rounded_down() {
+    let quote_data = usdc_wsol_quote_data();
+    let (reserve_a, reserve_b) = get_pool_reserves(&quote_data).unwrap();
+    let lp_amount = POOL_LP_SUPPLY / 3;
+
+    let (token_a_amount, token_b_amount) =
+        get_lp_token_amounts(lp_amount, POOL_LP_SUPPLY, &quote_data).unwrap();
+
+    assert_eq!(
+        u128::from(token_a_amount),
+        u128::from(lp_amount) * u128::from(reserve_a) / u128::from(POOL_LP_SUPPLY)
+    );
+    assert_eq!(
+        u128::from(token_b_amount),
+        u128::from(lp_amount) * u128::from(reserve_b) / u128::from(POOL_LP_SUPPLY)
+    );
+    // Every position together never withdraws more than the reserves
+    assert!(token_a_amount * 3 <= reserve_a);
+    assert!(token_b_amount * 3 <= reserve_b);
+}
+
+#[test]
+fn lp_amount_above_the_supply_is_rejected() {
+    let quote_data = usdc_wsol_quote_data();
+
+    assert!(get_lp_token_amounts(1, 0, &quote_data).is_err());
+    assert!(get_lp_token_amounts(POOL_LP_SUPPLY + 1, POOL_LP_SUPPLY, &quote_data).is_err());
+}
+
+#[test]
+fn lp_position_is_valued_in_both_tokens() {
+    let quote_data = usdc_wsol_quote_data();
+    let source = LpPositionSource::LockEscrow {
+        lock_escrow: Pubkey::new_unique(),
+    };
+    let lp_amount = 1_000_000_000;
+
+    let position = value_lp_position(
+        USDC_WSOL_POOL,
+        source,
+        lp_amount,
+        POOL_LP_SUPPLY,
+        &quote_data,
+    )
+    .unwrap();
+
+    assert_eq!(position.pool, USDC_WSOL_POOL);
+    assert_eq!(position.source, source);
+    assert_eq!(position.lp_amount, lp_amount);
+    assert_eq!(
+        (position.token_a_amount, position.token_b_amount),
+        get_lp_token_amounts(lp_amount, POOL_LP_SUPPLY, &quote_data).unwrap()
+    );
+}
@@ -1,32 +1,23 @@
-#[macro_use]
-extern crate lazy_static;
-
-pub mod constant;
-pub mod quote_service;
-pub mod rpc_service;
-pub mod state;
-
 use anchor_lang::prelude::*;
-use constant::RPC_URL;
-use quote_service::QuoteService;
-use rpc_service::RpcService;
+use blaze_meteora_test_task::constant::RPC_URL;
+use blaze_meteora_test_task::quote_service::QuoteService;
+use blaze_meteora_test_task::rpc_service::RpcService;
 use solana_program::pubkey::Pubkey;
 
 use std::str::FromStr;
 
-#[tokio::main]
-async fn main() -> Result<()> {
+fn main() -> Result<()> {
     // METAV
-    let metav_mint = Pubkey::from_str("HCgvbV9Qcf9TVGPGKMGbVEj8WwwVD6HhTt5E2i3qkeN9").unwrap();
+    let _metav_mint = Pubkey::from_str("HCgvbV9Qcf9TVGPGKMGbVEj8WwwVD6HhTt5E2i3qkeN9").unwrap();
 
     // USDC
-    let usdc_mint = Pubkey::from_str("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v").unwrap();
+    let _usdc_mint = Pubkey::from_str("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v").unwrap();
 
     // WSOL
     let wsol_mint = Pubkey::from_str("So11111111111111111111111111111111111111112").unwrap();
 
     // USDC/WSOL
-    let usdc_sol_pool_address =
+    let _usdc_sol_pool_address =
         Pubkey::from_str("5yuefgbJJpmFNK2iiYbLSpv1aZXq7F9AUKkZKErTYCvs").unwrap();
 
     // METAV/WSOL
@@ -0,0 +1,157 @@
+use crate::quote_service::{
+    apply_quote, compute_quote, sync_shared_vaults, QuoteData, QuoteResult,
+};
+use crate::state::Pool;
+use anyhow::{ensure, Context};
+use solana_program::pubkey::Pubkey;
+use std::collections::HashSet;
+
+/// Default number of chunks the order is split into
+pub const DEFAULT_SPLIT_STEPS: usize = 100;
+
+/// Part of the order routed to one pool
+#[derive(Debug, Clone)]
+pub struct PoolAllocation {
+    /// Pool address
+    pub pool: Pubkey,
+    /// Swap in amount routed to the pool
+    pub in_amount: u64,
+    /// Swap out amount of the pool
+    pub out_amount: u64,
+    /// Trade fee amount of the pool. Fee is charged based on in token mint.
+    pub fee: u64,
+}
+
+/// Allocation of an order across pools of the same pair
+#[derive(Debug, Clone)]
+pub struct OrderSplit {
+    /// One allocation per candidate pool, in the order of the candidates
+    pub allocations: Vec<PoolAllocation>,
+    /// Combined swap out amount
+    pub out_amount: u64,
+}
+
+/// Split `in_amount` across distinct pools of the same pair to maximize the combined output.
+///
+/// The order is cut into `steps` chunks and each chunk is routed to the pool where it adds the
+/// most output, which is optimal up to the chunk size since the output of a constant product
+/// pool is concave in the input.
+///
+/// Pools of a pair deposit into and withdraw from the same two vaults, so the allocations are
+/// then applied one after the other in the order of the candidates, each on the vault state left
+/// by the previous ones, and the split fails when they drain a vault reserve together.
+pub fn split_order(
+    in_token_mint: Pubkey,
+    in_amount: u64,
+    pools: &[(Pubkey, QuoteData)],
+    steps: usize,
+) -> anyhow::Result<OrderSplit> {
+    ensure!(!pools.is_empty(), "No candidate pool");
+    ensure!(steps > 0, "Steps must be positive");
+
+    let (_, first_quote_data) = &pools[0];
+    let mut pool_addresses = HashSet::with_capacity(pools.len());
+    for (pool_address, quote_data) in pools {
+        ensure!(
+            is_same_pair(&first_quote_data.pool, &quote_data.pool),
+            "Pool {} is not a pool of the same pair",
+            pool_address
+        );
+        // The same liquidity would be counted once per entry
+        ensure!(
+            pool_addresses.insert(*pool_address),
+            "Pool {} is a candidate more than once",
+            pool_address
+        );
+    }
+
+    let mut in_amounts = vec![0u64; pools.len()];
+    let mut out_amounts = vec![0u64; pools.len()];
+
+    let chunk = (in_amount / steps as u64).max(1);
+    let mut remaining = in_amount;
+    while remaining > 0 {
+        let step_amount = if remaining < chunk.saturating_mul(2) {
+            remaining
+        } else {
+            chunk
+        };
+
+        let mut best: Option<(usize, u64)> = None;
+        for (index, (_, quote_data)) in pools.iter().enumerate() {
+            let Ok(QuoteResult { out_amount, .. }) = compute_quote(
+                in_token_mint,
+                in_amounts[index] + step_amount,
+                quote_data.clone(),
+            ) else {
+                continue;
+            };
+            let gain = out_amount.saturating_sub(out_amounts[index]);
+            if best.is_none_or(|(best_index, best_out_amount)| {
+                gain > best_out_amount.saturating_sub(out_amounts[best_index])
+            }) {
+                best = Some((index, out_amount));
+            }
+        }
+
+        let (index, out_amount) = best.context("Order can not be filled by the pools")?;
+        in_amounts[index] += step_amount;
+        out_amounts[index] = out_amount;
+        remaining -= step_amount;
+    }
+
+    let mut states: Vec<QuoteData> = pools
+        .iter()
+        .map(|(_, quote_data)| quote_data.clone())
+        .collect();
+    let mut allocations = Vec::with_capacity(pools.len());
+    for (index, (pool_address, in_amount)) in pools
+        .iter()
+        .map(|(pool_address, _)| pool_address)
+        .zip(in_amounts)
+        .enumerate()
+    {
+        let QuoteResult { out_amount, fee } = if in_amount > 0 {
+            let (quote_result, quote_data) =
+                apply_quote(in_token_mint, in_amount, states[index].clone()).with_context(
+                    || format!("Fail to apply the allocation of pool {}", pool_address),
+                )?;
+            for state in states.iter_mut() {
+                sync_shared_vaults(&quote_data, state);
+            }
+            states[index] = quote_data;
+            quote_result
+        } else {
+            QuoteResult {
+                out_amount: 0,
+                fee: 0,
+            }
+        };
+        allocations.push(PoolAllocation {
+            pool: *pool_address,
+            in_amount,
+            out_amount,
+            fee,
+        });
+    }
+
+    let out_amount = allocations
+        .iter()
+        .try_fold(0u64, |total, allocation| {
+            total.checked_add(allocation.out_amount)
+        })
+        .context("Fail to sum out amount")?;
+
+    Ok(OrderSplit {
+        allocations,
+        out_amount,
+    })
+}
+
+fn is_same_pair(pool: &Pool, other: &Pool) -> bool {
+    (pool.token_a_mint == other.token_a_mint && pool.token_b_mint == other.token_b_mint)
+        || (pool.token_a_mint == other.token_b_mint && pool.token_b_mint == other.token_a_mint)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,145 @@
+use super::*;
+use crate::test_fixtures::*;
+
+fn candidate(fixture: &PoolFixture) -> (Pubkey, QuoteData) {
+    (
+        fixture.pool_address,
+        fixture.snapshot().to_quote_data().unwrap(),
+    )
+}
+
+/// USDC/WSOL pool with a third of the liquidity of `usdc_wsol_pool`, at about the same price
+fn small_usdc_wsol_pool() -> PoolFixture {
+    PoolFixture {
+        pool_address: Pubkey::new_unique(),
+        vault_a: usdc_vault(507_554_970_000),
+        vault_b: wsol_vault(2_367_481_039_335),
+        ..usdc_wsol_pool()
+    }
+}
+
+#[test]
+fn two_pool_split_beats_the_best_single_pool() {
+    let pools = [
+        candidate(&usdc_wsol_pool()),
+        candidate(&small_usdc_wsol_pool()),
+    ];
+    let in_amount = 2_000_000_000_000;
+
+    let split = split_order(WSOL_MINT, in_amount, &pools, DEFAULT_SPLIT_STEPS).unwrap();
+
+    let best_single_pool = pools
+        .iter()
+        .map(|(_, quote_data)| {
+            compute_quote(WSOL_MINT, in_amount, quote_data.clone())
+                .unwrap()
+                .out_amount
+        })
+        .max()
+        .unwrap();
+    assert!(split.out_amount > best_single_pool);
+    assert!(split
+        .allocations
+        .iter()
+        .all(|allocation| allocation.in_amount > 0));
+    assert_eq!(
+        split
+            .allocations
+            .iter()
+            .map(|allocation| allocation.in_amount)
+            .sum::<u64>(),
+        in_amount
+    );
+    assert_eq!(
+        split
+            .allocations
+            .iter()
+            .map(|allocation| allocation.out_amount)
+            .sum::<u64>(),
+        split.out_amount
+    );
+    // The deeper pool takes the larger part of the order
+    assert!(split.allocations[0].in_amount > split.allocations[1].in_amount);
+}
+
+#[test]
+fn single_pool_gets_the_whole_order() {
+    let pools = [candidate(&usdc_wsol_pool())];
+
+    let split = split_order(USDC_MINT, 100_000_000, &pools, DEFAULT_SPLIT_STEPS).unwrap();
+
+    assert_eq!(split.allocations.len(), 1);
+    assert_eq!(split.allocations[0].pool, USDC_WSOL_POOL);
+    assert_eq!(split.allocations[0].in_amount, 100_000_000);
+    assert_eq!(
+        split.out_amount,
+        compute_quote(USDC_MINT, 100_000_000, pools[0].1.clone())
+            .unwrap()
+            .out_amount
+    );
+}
+
+#[test]
+fn pools_of_the_reversed_pair_are_the_same_pair() {
+    let small_pool = small_usdc_wsol_pool();
+    let reversed_pool = PoolFixture {
+        vault_a: small_pool.vault_b,
+        vault_b: small_pool.vault_a,
+        ..small_pool
+    };
+    let pools = [candidate(&usdc_wsol_pool()), candidate(&reversed_pool)];
+
+    assert!(split_order(WSOL_MINT, 2_000_000_000_000, &pools, DEFAULT_SPLIT_STEPS).is_ok());
+}
+
+#[test]
+fn pools_of_another_pair_are_rejected() {
+    let pools = [candidate(&usdc_wsol_pool()), candidate(&metav_wsol_pool())];
+
+    assert!(split_order(WSOL_MINT, 1_000_000_000, &pools, DEFAULT_SPLIT_STEPS).is_err());
+}
+
+#[test]
+fn duplicate_pools_are_rejected() {
+    // Splitting across two entries of the same pool would count its liquidity twice
+    let pools = [candidate(&usdc_wsol_pool()), candidate(&usdc_wsol_pool())];
+
+    assert!(split_order(WSOL_MINT, 2_000_000_000_000, &pools, DEFAULT_SPLIT_STEPS).is_err());
+}
+
+#[test]
+fn allocations_draining_the_shared_reserve_together_are_rejected() {
+    // Most of the USDC vault is lent out, 100 USDC are left in its reserve
+    let usdc_vault = VaultFixture {
+        token_vault_amount: 100_000_000,
+        ..usdc_vault(1_522_664_910_001)
+    };
+    let pool = PoolFixture {
+        vault_a: usdc_vault,
+        ..usdc_wsol_pool()
+    };
+    let other_pool = PoolFixture {
+        pool_address: Pubkey::new_unique(),
+        ..pool
+    };
+    let pools = [candidate(&pool), candidate(&other_pool)];
+    let in_amount = 600_000_000;
+
+    // Half of the order gets about 64 USDC out of each pool, which the reserve pays alone
+    for (_, quote_data) in &pools {
+        let out_amount = compute_quote(WSOL_MINT, in_amount / 2, quote_data.clone())
+            .unwrap()
+            .out_amount;
+        assert!(out_amount < usdc_vault.token_vault_amount);
+        assert!(out_amount * 2 > usdc_vault.token_vault_amount);
+    }
+    assert!(split_order(WSOL_MINT, in_amount, &pools, DEFAULT_SPLIT_STEPS).is_err());
+}
+
+#[test]
+fn invalid_arguments_are_rejected() {
+    let pools = [candidate(&usdc_wsol_pool())];
+
+    assert!(split_order(WSOL_MINT, 1_000_000_000, &[], DEFAULT_SPLIT_STEPS).is_err());
+    assert!(split_order(WSOL_MINT, 1_000_000_000, &pools, 0).is_err());
+}
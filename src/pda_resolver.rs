@@ -0,0 +1,225 @@
+use crate::constant::{
+    METEORA_DYN_PROGRAM_ID, METEORA_DYN_VAULT_BASE_ID, METEORA_DYN_VAULT_PROGRAM_ID,
+};
+use crate::quote_service::{get_all_accounts_for_quote, MeteoraAccounts};
+use crate::snapshot::pubkey_string;
+use anyhow::{ensure, Context};
+use serde::{Deserialize, Serialize};
+use solana_program::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{PoisonError, RwLock};
+
+/// Version of the cache file format. Bump it whenever the layout of the file changes.
+pub const PDA_CACHE_VERSION: u32 = 1;
+
+/// Pool and token mints the accounts are derived from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct PoolMints {
+    /// Address of the pool
+    #[serde(with = "pubkey_string")]
+    pub pool: Pubkey,
+    /// Token A mint of the pool
+    #[serde(with = "pubkey_string")]
+    pub token_a_mint: Pubkey,
+    /// Token B mint of the pool
+    #[serde(with = "pubkey_string")]
+    pub token_b_mint: Pubkey,
+}
+
+/// Resolve the PDA accounts of pools, deriving them once per pool.
+/// The cache is shared between threads and can be saved to disk to survive restarts.
+#[derive(Debug, Default)]
+pub struct PdaResolver {
+    accounts: RwLock<HashMap<PoolMints, MeteoraAccounts>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PdaCacheFile {
+    version: u32,
+    /// Program IDs the accounts were derived with, the cache is stale when they change
+    #[serde(with = "pubkey_string")]
+    dynamic_amm_program: Pubkey,
+    #[serde(with = "pubkey_string")]
+    vault_program: Pubkey,
+    #[serde(with = "pubkey_string")]
+    vault_base: Pubkey,
+    entries: Vec<PdaCacheEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PdaCacheEntry {
+    #[serde(flatten)]
+    pool_mints: PoolMints,
+    accounts: MeteoraAccounts,
+}
+
+impl PdaResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the accounts of the pool, same as `get_all_accounts_for_quote` but derived only on
+    /// the first call for the pool
+    pub fn resolve(
+        &self,
+        token_a_mint: Pubkey,
+        token_b_mint: Pubkey,
+        pool: Pubkey,
+    ) -> MeteoraAccounts {
+        let pool_mints = PoolMints {
+            pool,
+            token_a_mint,
+            token_b_mint,
+        };
+        // Entries are inserted whole, so the map is still consistent after a panic elsewhere
+        if let Some(accounts) = self
+            .accounts
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&pool_mints)
+        {
+            return *accounts;
+        }
+
+        // Derived outside of the lock, a concurrent miss on the same pool derives the same accounts
+        let accounts = get_all_accounts_for_quote(token_a_mint, token_b_mint, pool);
+        self.accounts
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(pool_mints, accounts);
+
+        accounts
+    }
+
+    /// Resolve the accounts of the pool and check them against the on-chain state with `check`.
+    /// A cached entry failing the check, e.g. one loaded from a stale or edited cache file, is
+    /// derived again and replaced, so only the derived accounts can reject the pool.
+    pub fn resolve_checked<T>(
+        &self,
+        token_a_mint: Pubkey,
+        token_b_mint: Pubkey,
+        pool: Pubkey,
+        check: impl Fn(&MeteoraAccounts) -> anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        let accounts = self.resolve(token_a_mint, token_b_mint, pool);
+        let error = match check(&accounts) {
+            Ok(value) => return Ok(value),
+            Err(error) => error,
+        };
+
+        let derived = get_all_accounts_for_quote(token_a_mint, token_b_mint, pool);
+        if derived == accounts {
+            return Err(error);
+        }
+        self.accounts
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(
+                PoolMints {
+                    pool,
+                    token_a_mint,
+                    token_b_mint,
+                },
+                derived,
+            );
+
+        check(&derived)
+    }
+
+    /// Number of cached pools
+    pub fn len(&self) -> usize {
+        self.accounts
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Export the cache as JSON, sorted by pool
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        let mut entries: Vec<_> = self
+            .accounts
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|(pool_mints, accounts)| PdaCacheEntry {
+                pool_mints: *pool_mints,
+                accounts: *accounts,
+            })
+            .collect();
+        entries.sort_by_key(|entry| entry.pool_mints);
+
+        serde_json::to_string(&PdaCacheFile {
+            version: PDA_CACHE_VERSION,
+            dynamic_amm_program: METEORA_DYN_PROGRAM_ID,
+            vault_program: METEORA_DYN_VAULT_PROGRAM_ID,
+            vault_base: METEORA_DYN_VAULT_BASE_ID,
+            entries,
+        })
+        .context("Fail to serialize PDA cache")
+    }
+
+    /// Import a cache from JSON, rejecting other versions of the format and other program IDs
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        let file: PdaCacheFile =
+            serde_json::from_str(json).context("Fail to deserialize PDA cache")?;
+        ensure!(
+            file.version == PDA_CACHE_VERSION,
+            "Unsupported PDA cache version {}, expected {}",
+            file.version,
+            PDA_CACHE_VERSION
+        );
+        ensure!(
+            file.dynamic_amm_program == METEORA_DYN_PROGRAM_ID
+                && file.vault_program == METEORA_DYN_VAULT_PROGRAM_ID
+                && file.vault_base == METEORA_DYN_VAULT_BASE_ID,
+            "PDA cache derived with other program IDs"
+        );
+
+        Ok(Self {
+            accounts: RwLock::new(
+                file.entries
+                    .into_iter()
+                    .map(|entry| (entry.pool_mints, entry.accounts))
+                    .collect(),
+            ),
+        })
+    }
+
+    /// Write the cache to a JSON file. The file is replaced at once, so a crash while saving
+    /// keeps the previous cache.
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, self.to_json()?)
+            .with_context(|| format!("Fail to write PDA cache to {}", tmp_path.display()))?;
+        std::fs::rename(&tmp_path, path)
+            .with_context(|| format!("Fail to write PDA cache to {}", path.display()))
+    }
+
+    /// Read a cache from a JSON file
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Fail to read PDA cache from {}", path.display()))?;
+
+        Self::from_json(&json)
+    }
+
+    /// Read a cache from a JSON file, or start an empty one when the file does not exist
+    pub fn load_or_default(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+
+        Self::load(path)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,137 @@
+use super::*;
+use crate::test_fixtures::{USDC_MINT, USDC_WSOL_POOL, WSOL_MINT};
+
+fn temp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("{}-{}.json", name, std::process::id()))
+}
+
+#[test]
+fn resolved_accounts_match_derived_ones() {
+    let resolver = PdaResolver::new();
+
+    for _ in 0..2 {
+        assert_eq!(
+            resolver.resolve(USDC_MINT, WSOL_MINT, USDC_WSOL_POOL),
+            get_all_accounts_for_quote(USDC_MINT, WSOL_MINT, USDC_WSOL_POOL)
+        );
+    }
+    assert_eq!(resolver.len(), 1);
+
+    // The mints are part of the key, swapping them derives the accounts again
+    assert_eq!(
+        resolver.resolve(WSOL_MINT, USDC_MINT, USDC_WSOL_POOL),
+        get_all_accounts_for_quote(WSOL_MINT, USDC_MINT, USDC_WSOL_POOL)
+    );
+    assert_eq!(resolver.len(), 2);
+}
+
+#[test]
+fn resolver_is_shared_between_threads() {
+    let resolver = PdaResolver::new();
+    let pools: Vec<Pubkey> = (0..8).map(|_| Pubkey::new_unique()).collect();
+
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for pool in &pools {
+                    resolver.resolve(USDC_MINT, WSOL_MINT, *pool);
+                }
+            });
+        }
+    });
+
+    assert_eq!(resolver.len(), pools.len());
+}
+
+#[test]
+fn cache_file_round_trip() {
+    let resolver = PdaResolver::new();
+    resolver.resolve(USDC_MINT, WSOL_MINT, USDC_WSOL_POOL);
+    resolver.resolve(USDC_MINT, WSOL_MINT, Pubkey::new_unique());
+    let path = temp_path("pda-cache-round-trip");
+
+    resolver.save(&path).unwrap();
+    let loaded = PdaResolver::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.to_json().unwrap(), resolver.to_json().unwrap());
+    assert_eq!(
+        loaded.resolve(USDC_MINT, WSOL_MINT, USDC_WSOL_POOL),
+        get_all_accounts_for_quote(USDC_MINT, WSOL_MINT, USDC_WSOL_POOL)
+    );
+    assert_eq!(loaded.len(), 2);
+}
+
+#[test]
+fn stale_cache_entry_is_derived_again() {
+    let derived = get_all_accounts_for_quote(USDC_MINT, WSOL_MINT, USDC_WSOL_POOL);
+    let resolver = PdaResolver::new();
+    resolver.resolve(USDC_MINT, WSOL_MINT, USDC_WSOL_POOL);
+    // Cache file edited by hand, with another vault for token A
+    let json = resolver.to_json().unwrap().replacen(
+        &derived.base_vault_authority.to_string(),
+        &Pubkey::new_unique().to_string(),
+        1,
+    );
+    let loaded = PdaResolver::from_json(&json).unwrap();
+    let stale = loaded.resolve(USDC_MINT, WSOL_MINT, USDC_WSOL_POOL);
+    assert_ne!(stale, derived);
+
+    let checked = loaded
+        .resolve_checked(USDC_MINT, WSOL_MINT, USDC_WSOL_POOL, |accounts| {
+            ensure!(*accounts == derived, "Accounts do not match the state");
+            Ok(*accounts)
+        })
+        .unwrap();
+
+    assert_eq!(checked, derived);
+    assert_eq!(
+        loaded.resolve(USDC_MINT, WSOL_MINT, USDC_WSOL_POOL),
+        derived
+    );
+    assert_eq!(loaded.len(), 1);
+}
+
+#[test]
+fn derived_accounts_failing_the_check_are_rejected() {
+    let resolver = PdaResolver::new();
+
+    let result: anyhow::Result<()> =
+        resolver.resolve_checked(USDC_MINT, WSOL_MINT, USDC_WSOL_POOL, |_| {
+            anyhow::bail!("Accounts do not match the state")
+        });
+
+    assert!(result.is_err());
+    assert_eq!(
+        resolver.resolve(USDC_MINT, WSOL_MINT, USDC_WSOL_POOL),
+        get_all_accounts_for_quote(USDC_MINT, WSOL_MINT, USDC_WSOL_POOL)
+    );
+}
+
+#[test]
+fn missing_cache_file_starts_empty() {
+    let resolver = PdaResolver::load_or_default(temp_path("pda-cache-missing")).unwrap();
+
+    assert!(resolver.is_empty());
+}
+
+#[test]
+fn cache_of_another_version_or_program_is_rejected() {
+    let resolver = PdaResolver::new();
+    resolver.resolve(USDC_MINT, WSOL_MINT, USDC_WSOL_POOL);
+    let json = resolver.to_json().unwrap();
+
+    let other_version = json.replacen(
+        &format!("\"version\":{}", PDA_CACHE_VERSION),
+        &format!("\"version\":{}", PDA_CACHE_VERSION + 1),
+        1,
+    );
+    assert!(PdaResolver::from_json(&other_version).is_err());
+
+    let other_program = json.replacen(
+        &METEORA_DYN_VAULT_BASE_ID.to_string(),
+        &Pubkey::new_unique().to_string(),
+        1,
+    );
+    assert!(PdaResolver::from_json(&other_program).is_err());
+}
@@ -0,0 +1,142 @@
+use crate::quote_service::QuoteData;
+use crate::state::*;
+use solana_program::pubkey::Pubkey;
+
+/// Reason why a pool can not be traded
+#[derive(Debug, Clone, PartialEq)]
+pub enum PoolIssue {
+    /// Pool is disabled
+    PoolDisabled,
+    /// Pool does not use the constant product curve
+    UnsupportedCurveType,
+    /// Activation type is neither slot nor timestamp
+    InvalidActivationType(u8),
+    /// Pool is not activated yet
+    NotActivated {
+        activation_point: u64,
+        current_point: u64,
+    },
+    /// Trade fee numerator is above the denominator, a zero denominator included
+    InvalidTradeFee { numerator: u64, denominator: u64 },
+    /// Protocol trade fee numerator is above the denominator, a zero denominator included
+    InvalidProtocolTradeFee { numerator: u64, denominator: u64 },
+    /// Clock unix timestamp is negative, so the vault locked profit can not be computed
+    InvalidUnixTimestamp(i64),
+    /// Vault is disabled. Deposits, and therefore swaps into the vault, are blocked.
+    VaultDisabled { vault: Pubkey },
+    /// Vault has no LP minted
+    ZeroVaultLpSupply { vault: Pubkey },
+    /// Pool holds no token of the mint
+    ZeroReserve { token_mint: Pubkey },
+    /// Pool reserve can not be computed from the vault share
+    InvalidReserve { token_mint: Pubkey },
+}
+
+/// Validate the pool and its vaults, returning every problem found. An empty list means the pool is tradeable.
+///
+/// `Pool.pool_type` is not checked: it only tells whether the pool was created by the admin or by
+/// anyone, swaps do not depend on it, and a type unknown to the program fails to decode.
+pub fn validate_quote_data(quote_data: &QuoteData) -> Vec<PoolIssue> {
+    let QuoteData {
+        pool,
+        curve_type,
+        vault_a,
+        vault_b,
+        pool_vault_a_lp_token,
+        pool_vault_b_lp_token,
+        vault_a_lp_mint_supply,
+        vault_b_lp_mint_supply,
+        clock,
+        ..
+    } = quote_data;
+
+    let mut issues = vec![];
+
+    if !pool.enabled {
+        issues.push(PoolIssue::PoolDisabled);
+    }
+
+    if !matches!(curve_type, CurveType::ConstantProduct) {
+        issues.push(PoolIssue::UnsupportedCurveType);
+    }
+
+    match pool.bootstrapping.get_current_point(clock) {
+        Ok(current_point) => {
+            if current_point < pool.bootstrapping.activation_point {
+                issues.push(PoolIssue::NotActivated {
+                    activation_point: pool.bootstrapping.activation_point,
+                    current_point,
+                });
+            }
+        }
+        Err(_) => issues.push(PoolIssue::InvalidActivationType(
+            pool.bootstrapping.activation_type,
+        )),
+    }
+
+    // A zero fee is never divided, so its denominator does not matter
+    let is_invalid_fee = |numerator: u64, denominator: u64| numerator > denominator;
+    let fees = &pool.fees;
+    if is_invalid_fee(fees.trade_fee_numerator, fees.trade_fee_denominator) {
+        issues.push(PoolIssue::InvalidTradeFee {
+            numerator: fees.trade_fee_numerator,
+            denominator: fees.trade_fee_denominator,
+        });
+    }
+    if is_invalid_fee(
+        fees.protocol_trade_fee_numerator,
+        fees.protocol_trade_fee_denominator,
+    ) {
+        issues.push(PoolIssue::InvalidProtocolTradeFee {
+            numerator: fees.protocol_trade_fee_numerator,
+            denominator: fees.protocol_trade_fee_denominator,
+        });
+    }
+
+    let Ok(current_time) = clock.unix_timestamp.try_into() else {
+        issues.push(PoolIssue::InvalidUnixTimestamp(clock.unix_timestamp));
+        return issues;
+    };
+    for (vault_address, vault, pool_vault_lp_amount, vault_lp_mint_supply) in [
+        (
+            pool.a_vault,
+            vault_a,
+            pool_vault_a_lp_token.amount,
+            *vault_a_lp_mint_supply,
+        ),
+        (
+            pool.b_vault,
+            vault_b,
+            pool_vault_b_lp_token.amount,
+            *vault_b_lp_mint_supply,
+        ),
+    ] {
+        if vault.enabled != 1 {
+            issues.push(PoolIssue::VaultDisabled {
+                vault: vault_address,
+            });
+        }
+
+        if vault_lp_mint_supply == 0 {
+            issues.push(PoolIssue::ZeroVaultLpSupply {
+                vault: vault_address,
+            });
+            continue;
+        }
+
+        match vault.get_amount_by_share(current_time, pool_vault_lp_amount, vault_lp_mint_supply) {
+            Some(0) => issues.push(PoolIssue::ZeroReserve {
+                token_mint: vault.token_mint,
+            }),
+            Some(_) => {}
+            None => issues.push(PoolIssue::InvalidReserve {
+                token_mint: vault.token_mint,
+            }),
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests;
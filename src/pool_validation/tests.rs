@@ -0,0 +1,208 @@
+use super::*;
+use crate::test_fixtures::*;
+
+fn issues(fixture: &PoolFixture) -> Vec<PoolIssue> {
+    validate_quote_data(&fixture.snapshot().to_quote_data().unwrap())
+}
+
+fn issues_after(update: impl FnOnce(&mut QuoteData)) -> Vec<PoolIssue> {
+    let mut quote_data = usdc_wsol_pool().snapshot().to_quote_data().unwrap();
+    update(&mut quote_data);
+
+    validate_quote_data(&quote_data)
+}
+
+#[test]
+fn tradeable_pool_has_no_issue() {
+    assert_eq!(issues(&usdc_wsol_pool()), vec![]);
+    assert_eq!(issues(&metav_wsol_pool()), vec![]);
+    assert_eq!(issues(&legacy_vault_pool()), vec![]);
+}
+
+#[test]
+fn disabled_pool() {
+    assert_eq!(
+        issues_after(|quote_data| quote_data.pool.enabled = false),
+        vec![PoolIssue::PoolDisabled]
+    );
+}
+
+#[test]
+fn stable_curve() {
+    assert_eq!(
+        issues_after(|quote_data| {
+            quote_data.curve_type = CurveType::Stable {
+                amp: 100,
+                token_multiplier: TokenMultiplier::default(),
+                depeg: Depeg::default(),
+                last_amp_updated_timestamp: 0,
+            }
+        }),
+        vec![PoolIssue::UnsupportedCurveType]
+    );
+}
+
+#[test]
+fn invalid_activation_type() {
+    let fixture = PoolFixture {
+        activation_type: 2,
+        ..usdc_wsol_pool()
+    };
+
+    assert_eq!(issues(&fixture), vec![PoolIssue::InvalidActivationType(2)]);
+}
+
+#[test]
+fn not_activated() {
+    let fixture = PoolFixture {
+        activation_type: ActivationType::Timestamp as u8,
+        activation_point: UNIX_TIMESTAMP as u64 + 60,
+        ..usdc_wsol_pool()
+    };
+
+    assert_eq!(
+        issues(&fixture),
+        vec![PoolIssue::NotActivated {
+            activation_point: UNIX_TIMESTAMP as u64 + 60,
+            current_point: UNIX_TIMESTAMP as u64,
+        }]
+    );
+}
+
+#[test]
+fn invalid_fees() {
+    let fixture = PoolFixture {
+        fees: PoolFees {
+            trade_fee_numerator: 25,
+            trade_fee_denominator: 0,
+            protocol_trade_fee_numerator: 101,
+            protocol_trade_fee_denominator: 100,
+        },
+        ..usdc_wsol_pool()
+    };
+
+    assert_eq!(
+        issues(&fixture),
+        vec![
+            PoolIssue::InvalidTradeFee {
+                numerator: 25,
+                denominator: 0,
+            },
+            PoolIssue::InvalidProtocolTradeFee {
+                numerator: 101,
+                denominator: 100,
+            },
+        ]
+    );
+}
+
+#[test]
+fn zero_fees_ignore_their_denominators() {
+    let fixture = PoolFixture {
+        fees: PoolFees {
+            trade_fee_numerator: 0,
+            trade_fee_denominator: 0,
+            ..usdc_wsol_pool().fees
+        },
+        ..usdc_wsol_pool()
+    };
+
+    assert_eq!(issues(&fixture), vec![]);
+}
+
+#[test]
+fn permissioned_pool_is_tradeable() {
+    assert_eq!(
+        issues_after(|quote_data| quote_data.pool.pool_type = PoolType::Permissioned),
+        vec![]
+    );
+}
+
+#[test]
+fn negative_unix_timestamp() {
+    let fixture = PoolFixture {
+        unix_timestamp: -1,
+        ..usdc_wsol_pool()
+    };
+
+    assert_eq!(issues(&fixture), vec![PoolIssue::InvalidUnixTimestamp(-1)]);
+}
+
+#[test]
+fn zero_protocol_fee_ignores_its_denominator() {
+    let fixture = PoolFixture {
+        fees: PoolFees {
+            protocol_trade_fee_numerator: 0,
+            protocol_trade_fee_denominator: 0,
+            ..usdc_wsol_pool().fees
+        },
+        ..usdc_wsol_pool()
+    };
+
+    assert_eq!(issues(&fixture), vec![]);
+}
+
+#[test]
+fn disabled_vault() {
+    let mut quote_data = usdc_wsol_pool().snapshot().to_quote_data().unwrap();
+    quote_data.vault_b.enabled = 0;
+
+    assert_eq!(
+        validate_quote_data(&quote_data),
+        vec![PoolIssue::VaultDisabled {
+            vault: quote_data.pool.b_vault,
+        }]
+    );
+}
+
+#[test]
+fn vault_without_lp() {
+    let fixture = PoolFixture {
+        vault_a: VaultFixture {
+            lp_mint_supply: 0,
+            pool_lp_amount: 0,
+            ..usdc_vault(0)
+        },
+        ..usdc_wsol_pool()
+    };
+    let vault = fixture.snapshot().vault_a.address;
+
+    assert_eq!(
+        issues(&fixture),
+        vec![PoolIssue::ZeroVaultLpSupply { vault }]
+    );
+}
+
+#[test]
+fn pool_without_vault_lp() {
+    let fixture = PoolFixture {
+        vault_b: wsol_vault(0),
+        ..usdc_wsol_pool()
+    };
+
+    assert_eq!(
+        issues(&fixture),
+        vec![PoolIssue::ZeroReserve {
+            token_mint: WSOL_MINT,
+        }]
+    );
+}
+
+#[test]
+fn locked_profit_above_vault_total() {
+    let usdc_vault = usdc_vault(1_522_664_910_001);
+    let fixture = PoolFixture {
+        vault_a: VaultFixture {
+            locked_profit_tracker: locked_profit(usdc_vault.total_amount + 1, 0),
+            ..usdc_vault
+        },
+        ..usdc_wsol_pool()
+    };
+
+    assert_eq!(
+        issues(&fixture),
+        vec![PoolIssue::InvalidReserve {
+            token_mint: USDC_MINT,
+        }]
+    );
+}
@@ -0,0 +1,117 @@
+use crate::quote_service::{compute_quote, get_pool_reserves, QuoteData, QuoteResult};
+use crate::snapshot::pubkey_string;
+use anyhow::{ensure, Context};
+use serde::Serialize;
+use solana_program::pubkey::Pubkey;
+use std::fmt::Write;
+
+/// Quote of a single input amount of the ladder
+#[derive(Debug, Clone, Serialize)]
+pub struct LadderPoint {
+    /// Swap in amount
+    pub in_amount: u64,
+    /// Swap out amount
+    pub out_amount: u64,
+    /// Trade fee amount, charged in the in token
+    pub fee: u64,
+    /// Relative difference between the execution price and the spot price of the pool reserves,
+    /// fees included
+    pub price_impact: f64,
+}
+
+/// Quotes of many input amounts against one snapshot of the pool
+#[derive(Debug, Clone, Serialize)]
+pub struct QuoteLadder {
+    /// Mint of the token swapped into the pool
+    #[serde(with = "pubkey_string")]
+    pub in_token_mint: Pubkey,
+    /// Slot of the snapshot the ladder is quoted against
+    pub slot: u64,
+    /// Spot price of the pool reserves, in out token per in token
+    pub spot_price: f64,
+    /// One point per quoted amount, in the order of the amounts
+    pub points: Vec<LadderPoint>,
+}
+
+impl QuoteLadder {
+    /// Export the points as CSV, with a header row
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("in_amount,out_amount,fee,price_impact\n");
+        for point in &self.points {
+            // Writing into a String can not fail
+            let _ = writeln!(
+                csv,
+                "{},{},{},{}",
+                point.in_amount, point.out_amount, point.fee, point.price_impact
+            );
+        }
+        csv
+    }
+
+    /// Export the ladder as JSON
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        serde_json::to_string_pretty(self).context("Fail to serialize quote ladder")
+    }
+}
+
+/// Quote every amount against the same quote data. Amounts that can not be quoted, e.g. above
+/// the pool reserve, are left out of the ladder.
+pub fn compute_ladder(
+    in_token_mint: Pubkey,
+    amounts: &[u64],
+    quote_data: &QuoteData,
+) -> anyhow::Result<QuoteLadder> {
+    let (token_a_amount, token_b_amount) = get_pool_reserves(quote_data)?;
+    let (in_reserve, out_reserve) = if in_token_mint == quote_data.pool.token_a_mint {
+        (token_a_amount, token_b_amount)
+    } else {
+        (token_b_amount, token_a_amount)
+    };
+    ensure!(in_reserve > 0, "In token reserve is empty");
+    let spot_price = out_reserve as f64 / in_reserve as f64;
+
+    let points = amounts
+        .iter()
+        .filter(|&&in_amount| in_amount > 0)
+        .filter_map(|&in_amount| {
+            let QuoteResult { out_amount, fee } =
+                compute_quote(in_token_mint, in_amount, quote_data.clone()).ok()?;
+            let execution_price = out_amount as f64 / in_amount as f64;
+            Some(LadderPoint {
+                in_amount,
+                out_amount,
+                fee,
+                price_impact: 1.0 - execution_price / spot_price,
+            })
+        })
+        .collect();
+
+    Ok(QuoteLadder {
+        in_token_mint,
+        slot: quote_data.clock.slot,
+        spot_price,
+        points,
+    })
+}
+
+/// Get `steps` amounts from `start` to `end`, spaced by a constant ratio. The range is empty
+/// when `start` is zero or above `end`.
+pub fn geometric_amounts(start: u64, end: u64, steps: usize) -> Vec<u64> {
+    if steps == 0 || start == 0 || end < start {
+        return vec![];
+    }
+    if steps == 1 {
+        return vec![start];
+    }
+
+    let ratio = (end as f64 / start as f64).powf(1.0 / (steps - 1) as f64);
+    let mut amounts: Vec<u64> = (0..steps)
+        .map(|step| (start as f64 * ratio.powi(step as i32)).round() as u64)
+        .collect();
+    // Small ranges round several steps to the same amount
+    amounts.dedup();
+    amounts
+}
+
+#[cfg(test)]
+mod tests;
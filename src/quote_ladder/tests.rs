@@ -0,0 +1,108 @@
+use super::*;
+use crate::test_fixtures::*;
+
+#[test]
+fn geometric_amounts_spans_the_range() {
+    assert_eq!(geometric_amounts(1, 1_000, 4), vec![1, 10, 100, 1_000]);
+    assert_eq!(
+        geometric_amounts(1_000_000, 1_000_000_000_000, 7),
+        vec![
+            1_000_000,
+            10_000_000,
+            100_000_000,
+            1_000_000_000,
+            10_000_000_000,
+            100_000_000_000,
+            1_000_000_000_000,
+        ]
+    );
+}
+
+#[test]
+fn geometric_amounts_edge_cases() {
+    assert!(geometric_amounts(1_000, 1_000_000, 0).is_empty());
+    assert_eq!(geometric_amounts(1_000, 1_000_000, 1), vec![1_000]);
+    assert!(geometric_amounts(0, 1_000_000, 5).is_empty());
+    assert!(geometric_amounts(1_000, 0, 5).is_empty());
+    assert!(geometric_amounts(1_000_000, 1_000, 5).is_empty());
+    // Steps rounding to the same amount are only kept once
+    assert_eq!(geometric_amounts(1_000, 1_000, 5), vec![1_000]);
+    assert_eq!(geometric_amounts(1, 2, 5), vec![1, 2]);
+}
+
+#[test]
+fn ladder_points_match_single_quotes() {
+    let quote_data = usdc_wsol_pool().snapshot().to_quote_data().unwrap();
+    let amounts = [0, 1_000_000, 1_000_000_000, 1_000_000_000_000];
+
+    let ladder = compute_ladder(WSOL_MINT, &amounts, &quote_data).unwrap();
+
+    assert_eq!(ladder.slot, SLOT);
+    assert_eq!(
+        ladder
+            .points
+            .iter()
+            .map(|point| point.in_amount)
+            .collect::<Vec<_>>(),
+        vec![1_000_000, 1_000_000_000, 1_000_000_000_000]
+    );
+    for point in &ladder.points {
+        let quote_result = compute_quote(WSOL_MINT, point.in_amount, quote_data.clone()).unwrap();
+        assert_eq!(point.out_amount, quote_result.out_amount);
+        assert_eq!(point.fee, quote_result.fee);
+    }
+    // Larger swaps move the price further
+    assert!(ladder
+        .points
+        .windows(2)
+        .all(|points| points[0].price_impact < points[1].price_impact));
+    assert!(ladder.points[0].price_impact > 0.0);
+}
+
+#[test]
+fn ladder_spot_price_is_the_reserve_ratio() {
+    let quote_data = usdc_wsol_pool().snapshot().to_quote_data().unwrap();
+    let (usdc_amount, wsol_amount) = get_pool_reserves(&quote_data).unwrap();
+
+    let sell_wsol = compute_ladder(WSOL_MINT, &[], &quote_data).unwrap();
+    let sell_usdc = compute_ladder(USDC_MINT, &[], &quote_data).unwrap();
+
+    assert_eq!(
+        sell_wsol.spot_price,
+        usdc_amount as f64 / wsol_amount as f64
+    );
+    assert_eq!(
+        sell_usdc.spot_price,
+        wsol_amount as f64 / usdc_amount as f64
+    );
+    assert!(sell_wsol.points.is_empty());
+}
+
+#[test]
+fn ladder_of_empty_reserve_is_rejected() {
+    let fixture = PoolFixture {
+        vault_b: wsol_vault(0),
+        ..usdc_wsol_pool()
+    };
+    let quote_data = fixture.snapshot().to_quote_data().unwrap();
+
+    assert!(compute_ladder(WSOL_MINT, &[1_000_000], &quote_data).is_err());
+}
+
+#[test]
+fn ladder_exports() {
+    let quote_data = usdc_wsol_pool().snapshot().to_quote_data().unwrap();
+    let ladder = compute_ladder(WSOL_MINT, &[1_000_000_000], &quote_data).unwrap();
+    let point = &ladder.points[0];
+
+    assert_eq!(
+        ladder.to_csv(),
+        format!(
+            "in_amount,out_amount,fee,price_impact\n{},{},{},{}\n",
+            point.in_amount, point.out_amount, point.fee, point.price_impact
+        )
+    );
+    let json: serde_json::Value = serde_json::from_str(&ladder.to_json().unwrap()).unwrap();
+    assert_eq!(json["in_token_mint"], WSOL_MINT.to_string());
+    assert_eq!(json["points"][0]["out_amount"], point.out_amount);
+}
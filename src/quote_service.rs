@@ -1,125 +1,31 @@
 use crate::constant;
+use crate::snapshot::pubkey_string;
 use crate::state::*;
-use anchor_lang::AnchorDeserialize;
+use anchor_lang::AccountDeserialize;
+use anchor_spl::token::spl_token;
+use anchor_spl::token::spl_token::solana_program::program_pack::Pack;
 use anchor_spl::token::TokenAccount;
 use anyhow::{anyhow, ensure, Context};
+use serde::{Deserialize, Serialize};
+use solana_program::clock::Clock;
 use solana_program::pubkey::Pubkey;
 use std::convert::TryInto;
-use std::str::FromStr;
 
 use constant::METEORA_DYN_PROGRAM_ID;
 use constant::METEORA_DYN_VAULT_BASE_ID;
 use constant::METEORA_DYN_VAULT_PROGRAM_ID;
-use constant::VAULT_WITH_NON_PDA_BASED_LP_MINT;
 
-use crate::rpc_service::RpcService;
-
-pub struct QuoteService {
-    rpc_service: RpcService,
-}
-
-impl QuoteService {
-    pub fn new(rpc_service: RpcService) -> Self {
-        Self { rpc_service }
-    }
-
-    pub fn get_for_pair(
-        &self,
-        token_in: Pubkey,
-        pool_address: Pubkey,
-        amount: u64,
-    ) -> Result<QuoteResult, anyhow::Error> {
-        let rpc_service = &self.rpc_service;
-
-        // Get current slot and timestamp
-        let slot = rpc_service.get_slot();
-        let block_time = rpc_service.get_block_time(slot);
-
-        // Fetch the account data
-        let pool_account_data = rpc_service.get_account_data(&pool_address);
-
-        // 875
-        let pool = Pool::try_from_slice(&pool_account_data[8..532]).unwrap();
-
-        let MeteoraAccounts {
-            base_vault_authority,
-            quote_vault_authority,
-            base_token_vault,
-            quote_token_vault,
-            base_vault_lp_address,
-            quote_vault_lp_address,
-            base_vault_lp_mint_address,
-            quote_vault_lp_mint_address,
-        } = get_all_accounts_for_quote(pool.token_a_mint, pool.token_b_mint, pool_address);
-
-        println!("Pool: {:?}", pool);
-
-        let base_vault =
-            Vault::try_from_slice(&rpc_service.get_account_data(&base_vault_authority)[8..1227])
-                .unwrap();
-        let quote_vault =
-            Vault::try_from_slice(&rpc_service.get_account_data(&quote_vault_authority)[8..1227])
-                .unwrap();
-
-        // get mint account total supply
-        let base_vault_lp_mint = rpc_service.get_mint(&base_vault_lp_mint_address);
-        let quote_vault_lp_mint = rpc_service.get_mint(&quote_vault_lp_mint_address);
-
-        // Get token accounts
-        let pool_vault_a_lp_token = rpc_service.get_token_account(&base_vault_lp_address);
-        let pool_vault_b_lp_token = rpc_service.get_token_account(&quote_vault_lp_address);
-
-        let vault_a_token = rpc_service.get_token_account(&base_token_vault);
-        let vault_b_token = rpc_service.get_token_account(&quote_token_vault);
-
-        let quote_data = QuoteData {
-            pool,
-            vault_a: base_vault,
-            vault_b: quote_vault,
-            pool_vault_a_lp_token,
-            pool_vault_b_lp_token,
-            vault_a_lp_mint_supply: base_vault_lp_mint.supply,
-            vault_b_lp_mint_supply: quote_vault_lp_mint.supply,
-            vault_a_token,
-            vault_b_token,
-            slot: slot,
-            block_time: block_time,
-        };
-
-        // print every value from quote_data
-        println!("Vault A: {:?}", quote_data.vault_a.total_amount);
-        println!("Vault B: {:?}", quote_data.vault_b.total_amount);
-        println!(
-            "Pool Vault A LP Token: {:?}",
-            quote_data.pool_vault_a_lp_token
-        );
-        println!(
-            "Pool Vault B LP Token: {:?}",
-            quote_data.pool_vault_b_lp_token
-        );
-        println!(
-            "Vault A LP Mint Supply: {:?}",
-            quote_data.vault_a_lp_mint_supply
-        );
-        println!(
-            "Vault B LP Mint Supply: {:?}",
-            quote_data.vault_b_lp_mint_supply
-        );
-        println!("Vault A Token: {:?}", quote_data.vault_a_token);
-        println!("Vault B Token: {:?}", quote_data.vault_b_token);
-        println!("Slot: {:?}", quote_data.slot);
-        println!("Block Time: {:?}", quote_data.block_time);
-
-        let quote_result = compute_quote(token_in, amount, quote_data)?;
-
-        Ok(quote_result)
-    }
-}
+#[cfg(feature = "rpc")]
+mod service;
+#[cfg(feature = "rpc")]
+pub use service::{QuoteService, ROUTER_SNAPSHOT_POOLS};
 
 #[derive(Clone)]
-struct QuoteData {
+pub struct QuoteData {
     /// Pool state to swap
     pub pool: Pool,
+    /// Swap curve of the pool
+    pub curve_type: CurveType,
     /// Vault state of vault A
     pub vault_a: Vault,
     /// Vault state of vault B
@@ -136,13 +42,11 @@ struct QuoteData {
     pub vault_a_token: TokenAccount,
     /// Token account of vault B
     pub vault_b_token: TokenAccount,
-    /// Slot
-    pub slot: u64,
-    /// Epoch start timestamp
-    pub block_time: i64,
+    /// Clock sysvar, read together with the accounts above
+    pub clock: Clock,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub struct QuoteResult {
     /// Swap out amount
     pub out_amount: u64,
@@ -150,13 +54,132 @@ pub struct QuoteResult {
     pub fee: u64,
 }
 
-fn compute_quote(
+pub fn compute_quote(
+    in_token_mint: Pubkey,
+    in_amount: u64,
+    quote_data: QuoteData,
+) -> anyhow::Result<QuoteResult> {
+    compute_quote_for_user(in_token_mint, in_amount, quote_data, None)
+}
+
+/// Compute the quote for a swap made by `user`, `None` for any user. Before the activation point
+/// only the whitelisted vault of the pool is allowed to swap.
+pub fn compute_quote_for_user(
     in_token_mint: Pubkey,
     in_amount: u64,
     quote_data: QuoteData,
+    user: Option<Pubkey>,
 ) -> anyhow::Result<QuoteResult> {
+    let (quote_result, _) = apply_quote_for_user(in_token_mint, in_amount, quote_data, user)?;
+
+    Ok(quote_result)
+}
+
+/// Get the amounts of token A and token B held by the pool through its vault LP
+pub fn get_pool_reserves(quote_data: &QuoteData) -> anyhow::Result<(u64, u64)> {
+    let current_time: u64 = quote_data.clock.unix_timestamp.try_into()?;
+
+    let token_a_amount = quote_data
+        .vault_a
+        .get_amount_by_share(
+            current_time,
+            quote_data.pool_vault_a_lp_token.amount,
+            quote_data.vault_a_lp_mint_supply,
+        )
+        .context("Fail to get token a amount")?;
+
+    let token_b_amount = quote_data
+        .vault_b
+        .get_amount_by_share(
+            current_time,
+            quote_data.pool_vault_b_lp_token.amount,
+            quote_data.vault_b_lp_mint_supply,
+        )
+        .context("Fail to get token b amount")?;
+
+    Ok((token_a_amount, token_b_amount))
+}
+
+/// A swap of `in_amount` of `in_token_mint` into the pool
+#[derive(Debug, Clone, Copy)]
+pub struct Trade {
+    /// Mint of the token swapped into the pool
+    pub in_token_mint: Pubkey,
+    /// Amount swapped into the pool
+    pub in_amount: u64,
+}
+
+/// Compute the quote and apply the swap to the quote data. The returned quote data holds the
+/// pool, vault, LP supply and token account state after the swap, so that the next quote can run
+/// on top of it.
+pub fn apply_quote(
+    in_token_mint: Pubkey,
+    in_amount: u64,
+    quote_data: QuoteData,
+) -> anyhow::Result<(QuoteResult, QuoteData)> {
+    apply_quote_for_user(in_token_mint, in_amount, quote_data, None)
+}
+
+/// Apply the trades in order, each one quoted on top of the state left by the previous ones
+pub fn apply_trades(
+    trades: &[Trade],
+    quote_data: QuoteData,
+) -> anyhow::Result<(Vec<QuoteResult>, QuoteData)> {
+    let mut quote_results = Vec::with_capacity(trades.len());
+    let mut quote_data = quote_data;
+
+    for (index, trade) in trades.iter().enumerate() {
+        let (quote_result, next_quote_data) =
+            apply_quote(trade.in_token_mint, trade.in_amount, quote_data)
+                .with_context(|| format!("Fail to apply trade {}", index))?;
+        quote_results.push(quote_result);
+        quote_data = next_quote_data;
+    }
+
+    Ok((quote_results, quote_data))
+}
+
+/// Copy the state of the vaults `source` shares with `target` into `target`. Pools of the same
+/// token deposit into the same vault, so a swap applied to one pool changes the vault total,
+/// LP supply and token balance the other pool sees.
+pub fn sync_shared_vaults(source: &QuoteData, target: &mut QuoteData) {
+    for (vault_address, vault, vault_lp_mint_supply, vault_token) in [
+        (
+            source.pool.a_vault,
+            &source.vault_a,
+            source.vault_a_lp_mint_supply,
+            &source.vault_a_token,
+        ),
+        (
+            source.pool.b_vault,
+            &source.vault_b,
+            source.vault_b_lp_mint_supply,
+            &source.vault_b_token,
+        ),
+    ] {
+        if target.pool.a_vault == vault_address {
+            target.vault_a = *vault;
+            target.vault_a_lp_mint_supply = vault_lp_mint_supply;
+            target.vault_a_token = *vault_token;
+        }
+        if target.pool.b_vault == vault_address {
+            target.vault_b = *vault;
+            target.vault_b_lp_mint_supply = vault_lp_mint_supply;
+            target.vault_b_token = *vault_token;
+        }
+    }
+}
+
+/// Same as `apply_quote`, for a swap made by `user`, see `compute_quote_for_user`
+pub fn apply_quote_for_user(
+    in_token_mint: Pubkey,
+    in_amount: u64,
+    mut quote_data: QuoteData,
+    user: Option<Pubkey>,
+) -> anyhow::Result<(QuoteResult, QuoteData)> {
     let QuoteData {
         pool,
+        curve_type,
         vault_a,
         vault_b,
         pool_vault_a_lp_token,
@@ -165,92 +188,228 @@ fn compute_quote(
         vault_b_lp_mint_supply,
         vault_a_token,
         vault_b_token,
-        slot,
-        block_time,
-    } = quote_data;
-
-    let activation_type =
-        ActivationType::try_from(pool.bootstrapping.activation_type).map_err(|e| anyhow!(e))?;
-
-    let current_point = match activation_type {
-        ActivationType::Slot => slot,
-        ActivationType::Timestamp => block_time as u64,
+        clock,
+    } = &quote_data;
+
+    ensure_swappable(pool.enabled, curve_type, &pool.bootstrapping, clock, user)?;
+
+    let trade_direction = get_trade_direction(in_token_mint, pool.token_a_mint, pool.token_b_mint)?;
+
+    let SwapAmounts {
+        quote_result,
+        in_amount_after_protocol_fee,
+        in_lp,
+        out_vault_lp,
+    } = compute_swap(
+        &pool.fees,
+        trade_direction,
+        in_amount,
+        &PoolSide {
+            vault: vault_a.into(),
+            pool_vault_lp_amount: pool_vault_a_lp_token.amount,
+            vault_lp_mint_supply: *vault_a_lp_mint_supply,
+            vault_token_amount: vault_a_token.amount,
+        },
+        &PoolSide {
+            vault: vault_b.into(),
+            pool_vault_lp_amount: pool_vault_b_lp_token.amount,
+            vault_lp_mint_supply: *vault_b_lp_mint_supply,
+            vault_token_amount: vault_b_token.amount,
+        },
+        clock,
+    )?;
+    let out_amount = quote_result.out_amount;
+
+    // The in token (minus the protocol fee) is deposited into the in vault, and the out token
+    // withdrawn from the out vault by burning the pool's vault LP
+    let (
+        in_vault_state,
+        in_pool_vault_lp_token,
+        in_vault_lp_mint_supply,
+        in_vault_token,
+        out_vault_state,
+        out_pool_vault_lp_token,
+        out_vault_lp_mint_supply,
+        out_vault_token,
+    ) = match trade_direction {
+        TradeDirection::AtoB => (
+            &mut quote_data.vault_a,
+            &mut quote_data.pool_vault_a_lp_token,
+            &mut quote_data.vault_a_lp_mint_supply,
+            &mut quote_data.vault_a_token,
+            &mut quote_data.vault_b,
+            &mut quote_data.pool_vault_b_lp_token,
+            &mut quote_data.vault_b_lp_mint_supply,
+            &mut quote_data.vault_b_token,
+        ),
+        TradeDirection::BtoA => (
+            &mut quote_data.vault_b,
+            &mut quote_data.pool_vault_b_lp_token,
+            &mut quote_data.vault_b_lp_mint_supply,
+            &mut quote_data.vault_b_token,
+            &mut quote_data.vault_a,
+            &mut quote_data.pool_vault_a_lp_token,
+            &mut quote_data.vault_a_lp_mint_supply,
+            &mut quote_data.vault_a_token,
+        ),
     };
 
-    ensure!(pool.enabled, "Pool disabled");
+    in_vault_state.total_amount = in_vault_state
+        .total_amount
+        .checked_add(in_amount_after_protocol_fee)
+        .context("Fail to add in_vault.total_amount")?;
+    *in_pool_vault_lp_token = with_amount(
+        in_pool_vault_lp_token,
+        in_pool_vault_lp_token
+            .amount
+            .checked_add(in_lp)
+            .context("Fail to add in pool vault lp")?,
+    )?;
+    *in_vault_lp_mint_supply = in_vault_lp_mint_supply
+        .checked_add(in_lp)
+        .context("Fail to add in vault lp mint supply")?;
+    *in_vault_token = with_amount(
+        in_vault_token,
+        in_vault_token
+            .amount
+            .checked_add(in_amount_after_protocol_fee)
+            .context("Fail to add in vault token amount")?,
+    )?;
+
+    out_vault_state.total_amount = out_vault_state
+        .total_amount
+        .checked_sub(out_amount)
+        .context("Fail to sub out vault total amount")?;
+    *out_pool_vault_lp_token = with_amount(
+        out_pool_vault_lp_token,
+        out_pool_vault_lp_token
+            .amount
+            .checked_sub(out_vault_lp)
+            .context("Fail to sub out pool vault lp")?,
+    )?;
+    *out_vault_lp_mint_supply = out_vault_lp_mint_supply
+        .checked_sub(out_vault_lp)
+        .context("Fail to sub out vault lp mint supply")?;
+    *out_vault_token = with_amount(
+        out_vault_token,
+        out_vault_token
+            .amount
+            .checked_sub(out_amount)
+            .context("Fail to sub out vault token amount")?,
+    )?;
+
+    Ok((quote_result, quote_data))
+}
+
+/// Check that `user` can swap against the pool, `None` for any user. Before the activation
+/// point only the whitelisted vault of the pool is allowed to swap.
+pub(crate) fn ensure_swappable(
+    enabled: bool,
+    curve_type: &CurveType,
+    bootstrapping: &Bootstrapping,
+    clock: &Clock,
+    user: Option<Pubkey>,
+) -> anyhow::Result<()> {
+    let current_point = bootstrapping
+        .get_current_point(clock)
+        .map_err(|e| anyhow!(e))?;
+
+    ensure!(enabled, "Pool disabled");
+    ensure!(
+        matches!(curve_type, CurveType::ConstantProduct),
+        "Only constant product pools are supported"
+    );
     ensure!(
-        current_point >= pool.bootstrapping.activation_point,
+        current_point >= bootstrapping.activation_point
+            || user.is_some_and(|user| bootstrapping.is_whitelisted_vault(&user)),
         "Swap is disabled"
     );
 
-    let current_time: u64 = block_time.try_into()?;
+    Ok(())
+}
 
+/// Direction of a swap of `in_token_mint` into the pool of the token mints
+pub(crate) fn get_trade_direction(
+    in_token_mint: Pubkey,
+    token_a_mint: Pubkey,
+    token_b_mint: Pubkey,
+) -> anyhow::Result<TradeDirection> {
     ensure!(
-        in_token_mint == pool.token_a_mint || in_token_mint == pool.token_b_mint,
+        in_token_mint == token_a_mint || in_token_mint == token_b_mint,
         "In token mint not matches with pool token mints"
     );
 
-    let token_a_amount = vault_a
+    Ok(if in_token_mint == token_a_mint {
+        TradeDirection::AtoB
+    } else {
+        TradeDirection::BtoA
+    })
+}
+
+/// One token of the pool, held through the vault LP of the pool
+pub(crate) struct PoolSide {
+    /// Vault total amount and locked profit
+    pub vault: meteora_math::Vault,
+    /// Vault LP held by the pool
+    pub pool_vault_lp_amount: u64,
+    /// LP supply of the vault
+    pub vault_lp_mint_supply: u64,
+    /// Amount in the token account of the vault reserve
+    pub vault_token_amount: u64,
+}
+
+/// Amounts of a swap, see `compute_swap`
+pub(crate) struct SwapAmounts {
+    pub quote_result: QuoteResult,
+    /// In amount deposited into the in vault
+    pub in_amount_after_protocol_fee: u64,
+    /// In vault LP minted to the pool
+    pub in_lp: u64,
+    /// Out vault LP burnt by the pool
+    pub out_vault_lp: u64,
+}
+
+/// Compute the amounts of a swap the way the program does: deposit the in amount into the in
+/// vault, swap on the pool reserves, and withdraw the out amount from the out vault
+pub(crate) fn compute_swap(
+    fees: &PoolFees,
+    trade_direction: TradeDirection,
+    in_amount: u64,
+    side_a: &PoolSide,
+    side_b: &PoolSide,
+    clock: &Clock,
+) -> anyhow::Result<SwapAmounts> {
+    let current_time: u64 = clock.unix_timestamp.try_into()?;
+
+    let token_a_amount = side_a
+        .vault
         .get_amount_by_share(
             current_time,
-            pool_vault_a_lp_token.amount,
-            vault_a_lp_mint_supply,
+            side_a.pool_vault_lp_amount,
+            side_a.vault_lp_mint_supply,
         )
         .context("Fail to get token a amount")?;
 
-    let token_b_amount = vault_b
+    let token_b_amount = side_b
+        .vault
         .get_amount_by_share(
             current_time,
-            pool_vault_b_lp_token.amount,
-            vault_b_lp_mint_supply,
+            side_b.pool_vault_lp_amount,
+            side_b.vault_lp_mint_supply,
         )
         .context("Fail to get token b amount")?;
 
-    let trade_direction = if in_token_mint == pool.token_a_mint {
-        TradeDirection::AtoB
-    } else {
-        TradeDirection::BtoA
+    let (in_side, out_side, in_token_total_amount, out_token_total_amount) = match trade_direction {
+        TradeDirection::AtoB => (side_a, side_b, token_a_amount, token_b_amount),
+        TradeDirection::BtoA => (side_b, side_a, token_b_amount, token_a_amount),
     };
+    let mut in_vault = in_side.vault;
 
-    let (
-        mut in_vault,
-        out_vault,
-        in_vault_lp_amount,
-        in_vault_lp_mint_supply,
-        out_vault_lp_mint_supply,
-        out_vault_token_account,
-        in_token_total_amount,
-        out_token_total_amount,
-    ) = match trade_direction {
-        TradeDirection::AtoB => (
-            vault_a,
-            vault_b,
-            pool_vault_a_lp_token.amount,
-            vault_a_lp_mint_supply,
-            vault_b_lp_mint_supply,
-            vault_b_token,
-            token_a_amount,
-            token_b_amount,
-        ),
-        TradeDirection::BtoA => (
-            vault_b,
-            vault_a,
-            pool_vault_b_lp_token.amount,
-            vault_b_lp_mint_supply,
-            vault_a_lp_mint_supply,
-            vault_a_token,
-            token_b_amount,
-            token_a_amount,
-        ),
-    };
-
-    let trade_fee = pool
-        .fees
+    let trade_fee = fees
         .trading_fee(in_amount.into())
         .context("Fail to calculate trading fee")?;
 
-    let protocol_fee = pool
-        .fees
+    let protocol_fee = fees
         .protocol_trading_fee(trade_fee)
         .context("Fail to calculate protocol trading fee")?;
 
@@ -263,51 +422,34 @@ fn compute_quote(
         .checked_sub(protocol_fee.try_into()?)
         .context("Fail to calculate in_amount_after_protocol_fee")?;
 
-    println!(
-        "In Amount After Protocol Fee: {:?}",
-        in_amount_after_protocol_fee
-    );
-
     let before_in_token_total_amount = in_token_total_amount;
 
     let in_lp = in_vault
         .get_unmint_amount(
             current_time,
             in_amount_after_protocol_fee,
-            in_vault_lp_mint_supply,
+            in_side.vault_lp_mint_supply,
         )
         .context("Fail to get in_vault_lp")?;
 
-    println!("In LP: {:?}", in_lp);
-
     in_vault.total_amount = in_vault
         .total_amount
         .checked_add(in_amount_after_protocol_fee)
         .context("Fail to add in_vault.total_amount")?;
 
-    println!("In Vault Total Amount: {:?}", in_vault.total_amount);
-
     let after_in_token_total_amount = in_vault
         .get_amount_by_share(
             current_time,
             in_lp
-                .checked_add(in_vault_lp_amount)
+                .checked_add(in_side.pool_vault_lp_amount)
                 .context("Fail to get new in_vault_lp")?,
-            in_vault_lp_mint_supply
+            in_side
+                .vault_lp_mint_supply
                 .checked_add(in_lp)
                 .context("Fail to get new in_vault_lp_mint")?,
         )
         .context("Fail to get after_in_token_total_amount")?;
 
-    println!(
-        "After In Token Total Amount: {:?}",
-        after_in_token_total_amount
-    );
-    println!(
-        "Before In Token Total Amount: {:?}",
-        before_in_token_total_amount
-    );
-
     let actual_in_amount = after_in_token_total_amount
         .checked_sub(before_in_token_total_amount)
         .context("Fail to get actual_in_amount")?;
@@ -330,26 +472,33 @@ fn compute_quote(
         )
         .context("Fail to get swap result")?;
 
-    let out_vault_lp = out_vault
+    let out_vault_lp = out_side
+        .vault
         .get_unmint_amount(
             current_time,
             destination_amount_swapped.try_into()?,
-            out_vault_lp_mint_supply,
+            out_side.vault_lp_mint_supply,
         )
         .context("Fail to get out_vault_lp")?;
 
-    let out_amount = out_vault
-        .get_amount_by_share(current_time, out_vault_lp, out_vault_lp_mint_supply)
+    let out_amount = out_side
+        .vault
+        .get_amount_by_share(current_time, out_vault_lp, out_side.vault_lp_mint_supply)
         .context("Fail to get out_amount")?;
 
     ensure!(
-        out_amount < out_vault_token_account.amount,
+        out_amount < out_side.vault_token_amount,
         "Out amount > vault reserve"
     );
 
-    Ok(QuoteResult {
-        fee: trade_fee.try_into()?,
-        out_amount,
+    Ok(SwapAmounts {
+        quote_result: QuoteResult {
+            fee: trade_fee.try_into()?,
+            out_amount,
+        },
+        in_amount_after_protocol_fee,
+        in_lp,
+        out_vault_lp,
     })
 }
 
@@ -362,83 +511,52 @@ pub fn get_all_accounts_for_quote(
         &[
             b"vault",
             token_in.as_ref(),
-            &Pubkey::from_str(METEORA_DYN_VAULT_BASE_ID)
-                .unwrap()
-                .as_ref(),
+            METEORA_DYN_VAULT_BASE_ID.as_ref(),
         ],
-        &Pubkey::from_str(METEORA_DYN_VAULT_PROGRAM_ID).unwrap(),
+        &METEORA_DYN_VAULT_PROGRAM_ID,
     );
 
     let (quote_vault_authority, _) = Pubkey::find_program_address(
         &[
             b"vault",
             token_out.as_ref(),
-            &Pubkey::from_str(METEORA_DYN_VAULT_BASE_ID)
-                .unwrap()
-                .as_ref(),
+            METEORA_DYN_VAULT_BASE_ID.as_ref(),
         ],
-        &Pubkey::from_str(METEORA_DYN_VAULT_PROGRAM_ID).unwrap(),
+        &METEORA_DYN_VAULT_PROGRAM_ID,
     );
 
-    println!("A Vault Authority: {}", base_vault_authority); //A Vault
-    println!("B Vault Authority: {}", quote_vault_authority); //B Vault
-
     let (base_token_vault, _) = Pubkey::find_program_address(
-        &[b"token_vault", &base_vault_authority.as_ref()],
-        &Pubkey::from_str(METEORA_DYN_VAULT_PROGRAM_ID).unwrap(),
+        &[b"token_vault", base_vault_authority.as_ref()],
+        &METEORA_DYN_VAULT_PROGRAM_ID,
     );
 
     let (quote_token_vault, _) = Pubkey::find_program_address(
-        &[b"token_vault", &quote_vault_authority.as_ref()],
-        &Pubkey::from_str(METEORA_DYN_VAULT_PROGRAM_ID).unwrap(),
+        &[b"token_vault", quote_vault_authority.as_ref()],
+        &METEORA_DYN_VAULT_PROGRAM_ID,
     );
 
-    println!("A Token Vault: {}", base_token_vault); //A Token Vault
-    println!("B Token Vault: {}", quote_token_vault); //B Token Vault
-
-    let base_vault_lp_mint = VAULT_WITH_NON_PDA_BASED_LP_MINT
-        .get(&base_vault_authority)
-        .map_or_else(
-            || {
-                Pubkey::find_program_address(
-                    &[b"lp_mint", base_vault_authority.as_ref()],
-                    &Pubkey::from_str(METEORA_DYN_VAULT_PROGRAM_ID).unwrap(),
-                )
-                .0
-            },
-            |&lp_mint| lp_mint,
-        );
-
-    let quote_vault_lp_mint = VAULT_WITH_NON_PDA_BASED_LP_MINT
-        .get(&quote_vault_authority)
-        .map_or_else(
-            || {
-                Pubkey::find_program_address(
-                    &[b"lp_mint", quote_vault_authority.as_ref()],
-                    &Pubkey::from_str(METEORA_DYN_VAULT_PROGRAM_ID).unwrap(),
-                )
-                .0
-            },
-            |&lp_mint| lp_mint,
-        );
-
-    println!("A Vault LP Token Mint: {}", base_vault_lp_mint); //A Vault LP Mint
-    println!("B Vault LP Token Mint: {}", quote_vault_lp_mint); //B Vault LP Mint
+    // Legacy vaults have an LP mint that is not this PDA, see `check_accounts_for_quote`
+    let (base_vault_lp_mint, _) = Pubkey::find_program_address(
+        &[b"lp_mint", base_vault_authority.as_ref()],
+        &METEORA_DYN_VAULT_PROGRAM_ID,
+    );
+
+    let (quote_vault_lp_mint, _) = Pubkey::find_program_address(
+        &[b"lp_mint", quote_vault_authority.as_ref()],
+        &METEORA_DYN_VAULT_PROGRAM_ID,
+    );
 
     let (base_vault_lp_address, _) = Pubkey::find_program_address(
         &[base_vault_authority.as_ref(), pool_address.as_ref()],
-        &Pubkey::from_str(METEORA_DYN_PROGRAM_ID).unwrap(),
+        &METEORA_DYN_PROGRAM_ID,
     );
 
     let (quote_vault_lp_address, _) = Pubkey::find_program_address(
         &[quote_vault_authority.as_ref(), pool_address.as_ref()],
-        &Pubkey::from_str(METEORA_DYN_PROGRAM_ID).unwrap(),
+        &METEORA_DYN_PROGRAM_ID,
     );
 
-    println!("A LP Token Vault : {}", base_vault_lp_address); //A Vault LP
-    println!("V LP Token Vault : {}", quote_vault_lp_address); //B Vault LP
-
-    return MeteoraAccounts {
+    MeteoraAccounts {
         base_vault_authority,
         quote_vault_authority,
         base_token_vault,
@@ -447,16 +565,145 @@ pub fn get_all_accounts_for_quote(
         quote_vault_lp_address,
         base_vault_lp_mint_address: base_vault_lp_mint,
         quote_vault_lp_mint_address: quote_vault_lp_mint,
+    }
+}
+
+/// Copy of the token account holding `amount`. `TokenAccount` can not be mutated, so the copy is
+/// unpacked from the modified SPL token account.
+pub(crate) fn with_amount(
+    token_account: &TokenAccount,
+    amount: u64,
+) -> anyhow::Result<TokenAccount> {
+    let mut data = [0u8; TokenAccount::LEN];
+    spl_token::state::Account {
+        amount,
+        ..**token_account
+    }
+    .pack_into_slice(&mut data);
+
+    Ok(TokenAccount::try_deserialize_unchecked(&mut &data[..])?)
+}
+
+/// Derive the address of a permissionless constant product pool created from a config
+pub fn derive_pool_address_with_config(
+    token_a_mint: Pubkey,
+    token_b_mint: Pubkey,
+    config: Pubkey,
+) -> Pubkey {
+    let (first_key, second_key) = if token_a_mint > token_b_mint {
+        (token_a_mint, token_b_mint)
+    } else {
+        (token_b_mint, token_a_mint)
     };
+
+    Pubkey::find_program_address(
+        &[first_key.as_ref(), second_key.as_ref(), config.as_ref()],
+        &METEORA_DYN_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Resolve the accounts needed for a quote from the decoded pool and vault states
+pub fn get_accounts_from_state(pool: &Pool, vault_a: &Vault, vault_b: &Vault) -> MeteoraAccounts {
+    MeteoraAccounts {
+        base_vault_authority: pool.a_vault,
+        quote_vault_authority: pool.b_vault,
+        base_token_vault: vault_a.token_vault,
+        quote_token_vault: vault_b.token_vault,
+        base_vault_lp_address: pool.a_vault_lp,
+        quote_vault_lp_address: pool.b_vault_lp,
+        base_vault_lp_mint_address: vault_a.lp_mint,
+        quote_vault_lp_mint_address: vault_b.lp_mint,
+    }
+}
+
+/// Vault LP mints that are not the `lp_mint` PDA, found by `check_accounts_for_quote`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LegacyLpMints {
+    /// LP mint of vault A, when it is not the PDA
+    pub vault_a: Option<Pubkey>,
+    /// LP mint of vault B, when it is not the PDA
+    pub vault_b: Option<Pubkey>,
+}
+
+/// Check the accounts resolved from on-chain state against the PDA derived ones.
+/// Legacy vaults have an LP mint that is not the `lp_mint` PDA, so only that mismatch is allowed,
+/// and the legacy LP mints are returned.
+pub fn check_accounts_for_quote(
+    resolved: &MeteoraAccounts,
+    derived: &MeteoraAccounts,
+) -> anyhow::Result<LegacyLpMints> {
+    ensure!(
+        resolved.base_vault_authority == derived.base_vault_authority,
+        "Vault A does not match PDA: {} != {}",
+        resolved.base_vault_authority,
+        derived.base_vault_authority
+    );
+    ensure!(
+        resolved.quote_vault_authority == derived.quote_vault_authority,
+        "Vault B does not match PDA: {} != {}",
+        resolved.quote_vault_authority,
+        derived.quote_vault_authority
+    );
+    ensure!(
+        resolved.base_token_vault == derived.base_token_vault,
+        "Vault A token vault does not match PDA: {} != {}",
+        resolved.base_token_vault,
+        derived.base_token_vault
+    );
+    ensure!(
+        resolved.quote_token_vault == derived.quote_token_vault,
+        "Vault B token vault does not match PDA: {} != {}",
+        resolved.quote_token_vault,
+        derived.quote_token_vault
+    );
+    ensure!(
+        resolved.base_vault_lp_address == derived.base_vault_lp_address,
+        "Pool vault A LP does not match PDA: {} != {}",
+        resolved.base_vault_lp_address,
+        derived.base_vault_lp_address
+    );
+    ensure!(
+        resolved.quote_vault_lp_address == derived.quote_vault_lp_address,
+        "Pool vault B LP does not match PDA: {} != {}",
+        resolved.quote_vault_lp_address,
+        derived.quote_vault_lp_address
+    );
+
+    let legacy_lp_mint =
+        |resolved: Pubkey, derived: Pubkey| (resolved != derived).then_some(resolved);
+
+    Ok(LegacyLpMints {
+        vault_a: legacy_lp_mint(
+            resolved.base_vault_lp_mint_address,
+            derived.base_vault_lp_mint_address,
+        ),
+        vault_b: legacy_lp_mint(
+            resolved.quote_vault_lp_mint_address,
+            derived.quote_vault_lp_mint_address,
+        ),
+    })
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MeteoraAccounts {
+    #[serde(with = "pubkey_string")]
     pub base_vault_authority: Pubkey,
+    #[serde(with = "pubkey_string")]
     pub quote_vault_authority: Pubkey,
+    #[serde(with = "pubkey_string")]
     pub base_token_vault: Pubkey,
+    #[serde(with = "pubkey_string")]
     pub quote_token_vault: Pubkey,
+    #[serde(with = "pubkey_string")]
     pub base_vault_lp_address: Pubkey,
+    #[serde(with = "pubkey_string")]
     pub quote_vault_lp_address: Pubkey,
+    #[serde(with = "pubkey_string")]
     pub base_vault_lp_mint_address: Pubkey,
+    #[serde(with = "pubkey_string")]
     pub quote_vault_lp_mint_address: Pubkey,
 }
+
+#[cfg(test)]
+mod tests;
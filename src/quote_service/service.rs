@@ -0,0 +1,643 @@
+use super::*;
+use crate::arbitrage::{find_arbitrage_cycles, ArbitrageCycle};
+use crate::decoder::*;
+use crate::lp_analytics::PoolLpSnapshot;
+use crate::lp_position::{value_lp_position, LpPosition, LpPositionSource};
+use crate::order_split::{split_order, OrderSplit, DEFAULT_SPLIT_STEPS};
+use crate::pda_resolver::PdaResolver;
+use crate::pool_validation::{validate_quote_data, PoolIssue};
+use crate::quote_ladder::{compute_ladder, QuoteLadder};
+use crate::router::Router;
+use crate::rpc_service::RpcService;
+use crate::snapshot::{
+    QuoteSnapshot, SnapshotAccount, VaultSnapshot, QUOTE_SNAPSHOT_VERSION, VAULT_SNAPSHOT_VERSION,
+};
+use crate::vault_quote::{
+    compute_deposit_quote, compute_withdraw_quote, VaultQuoteData, VaultQuoteResult,
+};
+use crate::vault_solvency::{check_vault_solvency, VaultSolvencyReport};
+use solana_account::Account;
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_program::sysvar;
+use std::collections::{HashMap, HashSet};
+
+/// Pools snapshotted at one slot by `QuoteService::get_router_of_all_pools`
+pub const ROUTER_SNAPSHOT_POOLS: usize = 100;
+
+pub struct QuoteService {
+    rpc_service: RpcService,
+    pda_resolver: PdaResolver,
+}
+
+impl QuoteService {
+    pub fn new(rpc_service: RpcService) -> Self {
+        Self {
+            rpc_service,
+            pda_resolver: PdaResolver::new(),
+        }
+    }
+
+    /// Resolve the pool PDAs with `pda_resolver`, e.g. a cache loaded from disk
+    pub fn with_pda_resolver(self, pda_resolver: PdaResolver) -> Self {
+        Self {
+            pda_resolver,
+            ..self
+        }
+    }
+
+    /// Resolver of the pool PDAs, to save its cache
+    pub fn pda_resolver(&self) -> &PdaResolver {
+        &self.pda_resolver
+    }
+
+    pub fn get_for_pair(
+        &self,
+        token_in: Pubkey,
+        pool_address: Pubkey,
+        amount: u64,
+    ) -> Result<QuoteResult, anyhow::Error> {
+        let quote_data = self.get_quote_data(pool_address)?;
+
+        let quote_result = compute_quote(token_in, amount, quote_data)?;
+
+        Ok(quote_result)
+    }
+
+    /// Quote a swap made by the whitelisted (alpha) vault of the pool, which is allowed to buy
+    /// before the activation point
+    pub fn get_for_pair_as_whitelisted_vault(
+        &self,
+        token_in: Pubkey,
+        pool_address: Pubkey,
+        amount: u64,
+        whitelisted_vault: Pubkey,
+    ) -> anyhow::Result<QuoteResult> {
+        let quote_data = self.get_quote_data(pool_address)?;
+
+        compute_quote_for_user(token_in, amount, quote_data, Some(whitelisted_vault))
+    }
+
+    /// Get the slots or seconds left until the pool is activated
+    pub fn get_activation_countdown(
+        &self,
+        pool_address: Pubkey,
+    ) -> anyhow::Result<ActivationCountdown> {
+        let mut accounts = self
+            .rpc_service
+            .get_multiple_accounts(&[pool_address, sysvar::clock::ID])?
+            .into_iter()
+            .map(|account| account.map(|account| account.data));
+        let pool = decode_pool(&accounts.next().flatten().context("Pool not found")?)?;
+        let clock = decode_clock(
+            &accounts
+                .next()
+                .flatten()
+                .context("Clock sysvar not found")?,
+        )?;
+
+        pool.bootstrapping
+            .get_activation_countdown(&clock)
+            .map_err(|e| anyhow!(e))
+    }
+
+    /// Fetch and decode every config account of the dynamic AMM program
+    pub fn get_all_configs(&self) -> anyhow::Result<Vec<(Pubkey, Config)>> {
+        self.rpc_service
+            .get_program_accounts(
+                &METEORA_DYN_PROGRAM_ID,
+                vec![
+                    RpcFilterType::DataSize(CONFIG_ACCOUNT_SIZE as u64),
+                    RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                        0,
+                        Config::discriminator().to_vec(),
+                    )),
+                ],
+            )?
+            .into_iter()
+            .map(|(address, account)| {
+                let config = decode_config(&account.data)
+                    .with_context(|| format!("Fail to deserialize config {}", address))?;
+                Ok((address, config))
+            })
+            .collect()
+    }
+
+    /// Find the config the pool was created from. Returns `None` for pools not created from a config.
+    pub fn get_pool_config(
+        &self,
+        pool_address: Pubkey,
+    ) -> anyhow::Result<Option<(Pubkey, Config)>> {
+        let pool = decode_pool(&self.rpc_service.get_account_data(&pool_address)?)?;
+
+        Ok(self
+            .get_all_configs()?
+            .into_iter()
+            .find(|(config_address, _)| {
+                derive_pool_address_with_config(
+                    pool.token_a_mint,
+                    pool.token_b_mint,
+                    *config_address,
+                ) == pool_address
+            }))
+    }
+
+    /// Quote every amount against a single fetch of the pool
+    pub fn get_ladder(
+        &self,
+        token_in: Pubkey,
+        pool_address: Pubkey,
+        amounts: &[u64],
+    ) -> anyhow::Result<QuoteLadder> {
+        let quote_data = self.get_quote_data(pool_address)?;
+
+        compute_ladder(token_in, amounts, &quote_data)
+    }
+
+    /// Split the order across pools of the same pair to maximize the combined output
+    pub fn get_split_for_pair(
+        &self,
+        token_in: Pubkey,
+        pool_addresses: &[Pubkey],
+        amount: u64,
+    ) -> anyhow::Result<OrderSplit> {
+        let pools = self.get_quote_data_of_pools(pool_addresses)?;
+
+        split_order(token_in, amount, &pools, DEFAULT_SPLIT_STEPS)
+    }
+
+    /// Fetch and decode every pool account of the dynamic AMM program
+    pub fn get_all_pools(&self) -> anyhow::Result<Vec<(Pubkey, Pool)>> {
+        self.rpc_service
+            .get_program_accounts(
+                &METEORA_DYN_PROGRAM_ID,
+                vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                    0,
+                    Pool::discriminator().to_vec(),
+                ))],
+            )?
+            .into_iter()
+            .map(|(address, account)| {
+                let pool = decode_pool(&account.data)
+                    .with_context(|| format!("Fail to deserialize pool {}", address))?;
+                Ok((address, pool))
+            })
+            .collect()
+    }
+
+    /// Build a router over the pools, quoted on one snapshot of all of them
+    pub fn get_router(&self, pool_addresses: &[Pubkey]) -> anyhow::Result<Router> {
+        Ok(Router::new(self.get_quote_data_of_pools(pool_addresses)?))
+    }
+
+    /// Build a router over every pool of the program. Reading all of them at one slot is not
+    /// practical, so they are snapshotted in batches of `ROUTER_SNAPSHOT_POOLS`, each at one
+    /// slot, and the router slot is the latest of them.
+    pub fn get_router_of_all_pools(&self) -> anyhow::Result<Router> {
+        let pool_addresses: Vec<Pubkey> = self
+            .get_all_pools()?
+            .into_iter()
+            .map(|(pool_address, _)| pool_address)
+            .collect();
+
+        let mut pools = Vec::with_capacity(pool_addresses.len());
+        for batch in pool_addresses.chunks(ROUTER_SNAPSHOT_POOLS) {
+            pools.extend(self.get_quote_data_of_pools(batch)?);
+        }
+
+        Ok(Router::new(pools))
+    }
+
+    /// Find the profitable cycles through the base token across the pools
+    pub fn get_arbitrage_cycles(
+        &self,
+        base_mint: Pubkey,
+        pool_addresses: &[Pubkey],
+        max_hops: usize,
+        max_in_amount: u64,
+    ) -> anyhow::Result<Vec<ArbitrageCycle>> {
+        let pools = self.get_quote_data_of_pools(pool_addresses)?;
+
+        Ok(find_arbitrage_cycles(
+            base_mint,
+            &pools,
+            max_hops,
+            max_in_amount,
+        ))
+    }
+
+    /// Quote the vault LP minted for depositing `amount` into the vault
+    pub fn get_vault_deposit_quote(
+        &self,
+        vault_address: Pubkey,
+        amount: u64,
+    ) -> anyhow::Result<VaultQuoteResult> {
+        let vault_quote_data = self.get_vault_quote_data(vault_address)?;
+
+        compute_deposit_quote(amount, &vault_quote_data)
+    }
+
+    /// Quote the tokens returned for burning `lp_amount` of vault LP
+    pub fn get_vault_withdraw_quote(
+        &self,
+        vault_address: Pubkey,
+        lp_amount: u64,
+    ) -> anyhow::Result<VaultQuoteResult> {
+        let vault_quote_data = self.get_vault_quote_data(vault_address)?;
+
+        compute_withdraw_quote(lp_amount, &vault_quote_data)
+    }
+
+    /// Fetch the raw accounts needed to quote deposits into and withdrawals from the vault, so
+    /// the vault can be valued again later without RPC, e.g. to compute its yield over time
+    pub fn get_vault_snapshot(&self, vault_address: Pubkey) -> anyhow::Result<VaultSnapshot> {
+        let (snapshot, _) = self.get_vault_snapshot_with(vault_address, |_| vec![])?;
+
+        Ok(snapshot)
+    }
+
+    /// Fetch the vault snapshot and the accounts `other_addresses` derives from the vault state,
+    /// all in the same batch as the clock
+    fn get_vault_snapshot_with(
+        &self,
+        vault_address: Pubkey,
+        other_addresses: impl FnOnce(&Vault) -> Vec<Pubkey>,
+    ) -> anyhow::Result<(VaultSnapshot, Vec<Option<Account>>)> {
+        let rpc_service = &self.rpc_service;
+
+        let vault = decode_vault(
+            &rpc_service
+                .get_multiple_accounts(&[vault_address])?
+                .pop()
+                .flatten()
+                .context("Vault not found")?
+                .data,
+        )?;
+
+        // The vault is read again with the clock, see `get_quote_snapshot`
+        let addresses = [
+            &[
+                sysvar::clock::ID,
+                vault_address,
+                vault.lp_mint,
+                vault.token_vault,
+            ][..],
+            &other_addresses(&vault),
+        ]
+        .concat();
+        let fetched_accounts = rpc_service.get_multiple_accounts(&addresses)?;
+        let mut accounts = addresses.into_iter().zip(fetched_accounts);
+        let mut next_account = |name: &str| -> anyhow::Result<SnapshotAccount> {
+            accounts
+                .next()
+                .and_then(|(address, account)| Some(SnapshotAccount::new(address, account?)))
+                .with_context(|| format!("{} not found", name))
+        };
+
+        let clock = next_account("Clock sysvar")?;
+        let slot = decode_clock(&clock.data)?.slot;
+        let snapshot = VaultSnapshot {
+            version: VAULT_SNAPSHOT_VERSION,
+            slot,
+            clock,
+            vault: next_account("Vault")?,
+            lp_mint: next_account("Vault LP mint")?,
+            token_vault: next_account("Vault token")?,
+        };
+
+        Ok((snapshot, accounts.map(|(_, account)| account).collect()))
+    }
+
+    /// Fetch a snapshot of the vault and write it to a JSON file
+    pub fn save_vault_snapshot(
+        &self,
+        vault_address: Pubkey,
+        path: impl AsRef<std::path::Path>,
+    ) -> anyhow::Result<VaultSnapshot> {
+        let snapshot = self.get_vault_snapshot(vault_address)?;
+        snapshot.save(path)?;
+
+        Ok(snapshot)
+    }
+
+    /// Fetch every account needed to quote a deposit into or a withdrawal from the vault
+    pub fn get_vault_quote_data(&self, vault_address: Pubkey) -> anyhow::Result<VaultQuoteData> {
+        self.get_vault_snapshot(vault_address)?
+            .to_vault_quote_data()
+    }
+
+    /// Check the vault total amount against its reserve and the liquidity of its strategies. The
+    /// strategies and fee vault are read in the same batch as the vault.
+    pub fn get_vault_solvency(&self, vault_address: Pubkey) -> anyhow::Result<VaultSolvencyReport> {
+        let mut solvency_addresses = vec![];
+        let (snapshot, accounts) = self.get_vault_snapshot_with(vault_address, |vault| {
+            solvency_addresses = get_solvency_addresses(vault);
+            solvency_addresses.clone()
+        })?;
+        let vault_quote_data = snapshot.to_vault_quote_data()?;
+        // The fee vault and strategies were read from the vault state of the first read
+        ensure!(
+            get_solvency_addresses(&vault_quote_data.vault) == solvency_addresses,
+            "Vault fee vault or strategies changed between reads"
+        );
+        let strategy_addresses = solvency_addresses.split_off(1);
+
+        let mut accounts = accounts
+            .into_iter()
+            .map(|account| account.map(|account| account.data));
+
+        let fee_vault =
+            decode_token_account(&accounts.next().flatten().context("Fee vault not found")?)?;
+
+        let strategies = strategy_addresses
+            .into_iter()
+            .zip(accounts)
+            .map(|(strategy_address, data)| {
+                let data =
+                    data.with_context(|| format!("Strategy {} not found", strategy_address))?;
+                let strategy = decode_strategy(&data).with_context(|| {
+                    format!("Fail to deserialize strategy {}", strategy_address)
+                })?;
+                Ok((strategy_address, strategy))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        check_vault_solvency(&vault_quote_data, &fee_vault, &strategies)
+    }
+
+    /// Find the pool LP positions of the wallet, held in its token accounts or locked in its lock
+    /// escrows, and value them in both tokens of the pool
+    pub fn get_wallet_lp_positions(&self, wallet: Pubkey) -> anyhow::Result<Vec<LpPosition>> {
+        let pools = self.get_all_pools()?;
+        let pool_by_lp_mint: HashMap<Pubkey, Pubkey> = pools
+            .iter()
+            .map(|(pool_address, pool)| (pool.lp_mint, *pool_address))
+            .collect();
+
+        let mut holdings: Vec<(Pubkey, LpPositionSource, u64)> = vec![];
+
+        for (token_account_address, account) in self
+            .rpc_service
+            .get_token_accounts_by_owner(&wallet, &anchor_spl::token::ID)?
+        {
+            let token_account = decode_token_account(&account.data)?;
+            if token_account.amount == 0 {
+                continue;
+            }
+            if let Some(pool_address) = pool_by_lp_mint.get(&token_account.mint) {
+                holdings.push((
+                    *pool_address,
+                    LpPositionSource::Wallet {
+                        token_account: token_account_address,
+                    },
+                    token_account.amount,
+                ));
+            }
+        }
+
+        for (lock_escrow_address, account) in self.rpc_service.get_program_accounts(
+            &METEORA_DYN_PROGRAM_ID,
+            vec![
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                    0,
+                    LockEscrow::discriminator().to_vec(),
+                )),
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                    LOCK_ESCROW_OWNER_OFFSET,
+                    wallet.to_bytes().to_vec(),
+                )),
+            ],
+        )? {
+            let lock_escrow = decode_lock_escrow(&account.data).with_context(|| {
+                format!("Fail to deserialize lock escrow {}", lock_escrow_address)
+            })?;
+            if lock_escrow.total_locked_amount == 0 {
+                continue;
+            }
+            holdings.push((
+                lock_escrow.pool,
+                LpPositionSource::LockEscrow {
+                    lock_escrow: lock_escrow_address,
+                },
+                lock_escrow.total_locked_amount,
+            ));
+        }
+
+        // One snapshot per pool, so the LP supply is read in the same batch as the reserves and
+        // a pool held through several positions is fetched once
+        let snapshots = unique_addresses(holdings.iter().map(|(pool_address, _, _)| *pool_address))
+            .into_iter()
+            .map(|pool_address| Ok((pool_address, self.get_pool_lp_snapshot(pool_address)?)))
+            .collect::<anyhow::Result<HashMap<_, _>>>()?;
+
+        holdings
+            .into_iter()
+            .map(|(pool_address, source, lp_amount)| {
+                let PoolLpSnapshot {
+                    quote_data,
+                    pool_lp_supply,
+                } = &snapshots[&pool_address];
+                value_lp_position(pool_address, source, lp_amount, *pool_lp_supply, quote_data)
+            })
+            .collect()
+    }
+
+    /// Fetch the pool state together with the supply of the pool LP mint, read in one batch
+    pub fn get_pool_lp_snapshot(&self, pool_address: Pubkey) -> anyhow::Result<PoolLpSnapshot> {
+        self.get_quote_snapshot(pool_address)?.to_pool_lp_snapshot()
+    }
+
+    /// Validate the pool and its vaults, returning every problem that makes the pool untradeable
+    pub fn validate_pool(&self, pool_address: Pubkey) -> anyhow::Result<Vec<PoolIssue>> {
+        let quote_data = self.get_quote_data(pool_address)?;
+
+        Ok(validate_quote_data(&quote_data))
+    }
+
+    /// Fetch the raw accounts needed to quote a swap against the pool, so the quote can be
+    /// reproduced later without RPC
+    pub fn get_quote_snapshot(&self, pool_address: Pubkey) -> anyhow::Result<QuoteSnapshot> {
+        self.get_quote_snapshots(&[pool_address])?
+            .pop()
+            .context("Pool not found")
+    }
+
+    /// Fetch the quote snapshots of the pools, in the order of the addresses. The accounts of
+    /// every pool are read at one slot with one clock, see `RpcService::get_multiple_accounts`,
+    /// so the snapshots share the same slot and a vault shared by several pools has the same
+    /// state in all of them.
+    pub fn get_quote_snapshots(
+        &self,
+        pool_addresses: &[Pubkey],
+    ) -> anyhow::Result<Vec<QuoteSnapshot>> {
+        let rpc_service = &self.rpc_service;
+
+        let pools = pool_addresses
+            .iter()
+            .zip(rpc_service.get_multiple_accounts(pool_addresses)?)
+            .map(|(pool_address, account)| {
+                let account =
+                    account.with_context(|| format!("Pool {} not found", pool_address))?;
+                decode_pool(&account.data)
+                    .with_context(|| format!("Fail to deserialize pool {}", pool_address))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        // Vault addresses come from the pool, and the vault LP mints and token vaults from the
+        // vaults themselves, so vaults with a legacy (non PDA) LP mint resolve like any other.
+        let vault_addresses =
+            unique_addresses(pools.iter().flat_map(|pool| [pool.a_vault, pool.b_vault]));
+        let vaults = vault_addresses
+            .iter()
+            .zip(rpc_service.get_multiple_accounts(&vault_addresses)?)
+            .map(|(&vault_address, account)| {
+                let account =
+                    account.with_context(|| format!("Vault {} not found", vault_address))?;
+                let vault = decode_vault(&account.data)
+                    .with_context(|| format!("Fail to deserialize vault {}", vault_address))?;
+                Ok((vault_address, vault))
+            })
+            .collect::<anyhow::Result<HashMap<_, _>>>()?;
+
+        let pool_accounts = pool_addresses
+            .iter()
+            .zip(&pools)
+            .map(|(&pool_address, pool)| {
+                let meteora_accounts =
+                    get_accounts_from_state(pool, &vaults[&pool.a_vault], &vaults[&pool.b_vault]);
+                self.pda_resolver
+                    .resolve_checked(
+                        pool.token_a_mint,
+                        pool.token_b_mint,
+                        pool_address,
+                        |derived| check_accounts_for_quote(&meteora_accounts, derived),
+                    )
+                    .with_context(|| format!("Pool {} can not be quoted", pool_address))?;
+
+                let MeteoraAccounts {
+                    base_vault_authority,
+                    quote_vault_authority,
+                    base_token_vault,
+                    quote_token_vault,
+                    base_vault_lp_address,
+                    quote_vault_lp_address,
+                    base_vault_lp_mint_address,
+                    quote_vault_lp_mint_address,
+                } = meteora_accounts;
+                Ok([
+                    pool_address,
+                    pool.lp_mint,
+                    base_vault_authority,
+                    quote_vault_authority,
+                    base_vault_lp_mint_address,
+                    quote_vault_lp_mint_address,
+                    base_vault_lp_address,
+                    quote_vault_lp_address,
+                    base_token_vault,
+                    quote_token_vault,
+                ])
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        // The clock is read in the same batch as the accounts it is used with, so the slot and
+        // unix_timestamp match the state the program would see. The pools and vaults are read
+        // again for the same reason.
+        let addresses = unique_addresses(
+            std::iter::once(sysvar::clock::ID).chain(pool_accounts.iter().flatten().copied()),
+        );
+        let accounts: HashMap<Pubkey, Account> = addresses
+            .iter()
+            .copied()
+            .zip(rpc_service.get_multiple_accounts(&addresses)?)
+            .filter_map(|(address, account)| Some((address, account?)))
+            .collect();
+        let account = |address: Pubkey, name: &str| -> anyhow::Result<SnapshotAccount> {
+            let account = accounts
+                .get(&address)
+                .with_context(|| format!("{} {} not found", name, address))?;
+            Ok(SnapshotAccount::new(address, account.clone()))
+        };
+
+        let clock = account(sysvar::clock::ID, "Clock sysvar")?;
+        let slot = decode_clock(&clock.data)?.slot;
+
+        pool_accounts
+            .into_iter()
+            .map(
+                |[pool, pool_lp_mint, vault_a, vault_b, vault_a_lp_mint, vault_b_lp_mint, pool_vault_a_lp_token, pool_vault_b_lp_token, vault_a_token, vault_b_token]| {
+                    Ok(QuoteSnapshot {
+                        version: QUOTE_SNAPSHOT_VERSION,
+                        slot,
+                        clock: clock.clone(),
+                        pool: account(pool, "Pool")?,
+                        pool_lp_mint: account(pool_lp_mint, "Pool LP mint")?,
+                        vault_a: account(vault_a, "Vault A")?,
+                        vault_b: account(vault_b, "Vault B")?,
+                        vault_a_lp_mint: account(vault_a_lp_mint, "Vault A LP mint")?,
+                        vault_b_lp_mint: account(vault_b_lp_mint, "Vault B LP mint")?,
+                        pool_vault_a_lp_token: account(
+                            pool_vault_a_lp_token,
+                            "Pool vault A LP token",
+                        )?,
+                        pool_vault_b_lp_token: account(
+                            pool_vault_b_lp_token,
+                            "Pool vault B LP token",
+                        )?,
+                        vault_a_token: account(vault_a_token, "Vault A token")?,
+                        vault_b_token: account(vault_b_token, "Vault B token")?,
+                    })
+                },
+            )
+            .collect()
+    }
+
+    /// Fetch a snapshot of the pool and write it to a JSON file
+    pub fn save_quote_snapshot(
+        &self,
+        pool_address: Pubkey,
+        path: impl AsRef<std::path::Path>,
+    ) -> anyhow::Result<QuoteSnapshot> {
+        let snapshot = self.get_quote_snapshot(pool_address)?;
+        snapshot.save(path)?;
+
+        Ok(snapshot)
+    }
+
+    /// Fetch every account needed to quote a swap against the pool
+    pub fn get_quote_data(&self, pool_address: Pubkey) -> anyhow::Result<QuoteData> {
+        self.get_quote_snapshot(pool_address)?.to_quote_data()
+    }
+
+    /// Fetch the quote data of the pools from one snapshot, see `get_quote_snapshots`
+    pub fn get_quote_data_of_pools(
+        &self,
+        pool_addresses: &[Pubkey],
+    ) -> anyhow::Result<Vec<(Pubkey, QuoteData)>> {
+        pool_addresses
+            .iter()
+            .zip(self.get_quote_snapshots(pool_addresses)?)
+            .map(|(&pool_address, snapshot)| Ok((pool_address, snapshot.to_quote_data()?)))
+            .collect()
+    }
+}
+
+/// Deduplicate the addresses, keeping the first occurrence of each
+fn unique_addresses(addresses: impl IntoIterator<Item = Pubkey>) -> Vec<Pubkey> {
+    let mut seen = HashSet::new();
+    addresses
+        .into_iter()
+        .filter(|address| seen.insert(*address))
+        .collect()
+}
+
+/// Fee vault of the vault, then the strategies it lends to, skipping the empty slots
+fn get_solvency_addresses(vault: &Vault) -> Vec<Pubkey> {
+    std::iter::once(vault.fee_vault)
+        .chain(
+            vault
+                .strategies
+                .iter()
+                .copied()
+                .filter(|strategy| *strategy != Pubkey::default()),
+        )
+        .collect()
+}
@@ -0,0 +1,535 @@
+use super::*;
+use crate::snapshot::{QuoteSnapshot, RecordedSwap, QUOTE_SNAPSHOT_VERSION};
+use crate::test_fixtures::*;
+use proptest::prelude::{any, prop_assert, proptest};
+
+fn quote(fixture: &PoolFixture, in_token_mint: Pubkey, in_amount: u64) -> QuoteResult {
+    let quote_data = fixture.snapshot().to_quote_data().unwrap();
+
+    compute_quote(in_token_mint, in_amount, quote_data).unwrap()
+}
+
+// Expected amounts are regression values over the synthetic fixtures, see `test_fixtures`
+
+#[test]
+fn metav_wsol_buy_metav() {
+    assert_eq!(
+        quote(&metav_wsol_pool(), WSOL_MINT, 1_000_000_000),
+        QuoteResult {
+            out_amount: 1_185_776_262_476,
+            fee: 2_000_000,
+        }
+    );
+}
+
+#[test]
+fn metav_wsol_sell_metav() {
+    assert_eq!(
+        quote(&metav_wsol_pool(), METAV_MINT, 5_000_000_000_000),
+        QuoteResult {
+            out_amount: 4_195_053_038,
+            fee: 10_000_000_000,
+        }
+    );
+}
+
+#[test]
+fn usdc_wsol_sell_wsol() {
+    assert_eq!(
+        quote(&usdc_wsol_pool(), WSOL_MINT, 1_000_000_000),
+        QuoteResult {
+            out_amount: 214_799_641,
+            fee: 2_000_000,
+        }
+    );
+}
+
+#[test]
+fn usdc_wsol_buy_wsol() {
+    assert_eq!(
+        quote(&usdc_wsol_pool(), USDC_MINT, 100_000_000),
+        QuoteResult {
+            out_amount: 463_136_474,
+            fee: 200_000,
+        }
+    );
+}
+
+#[test]
+fn usdc_wsol_locked_profit_release() {
+    // An hour later more of the vault profit is unlocked, so the same LP is worth more
+    let fixture = PoolFixture {
+        unix_timestamp: UNIX_TIMESTAMP + 3_600,
+        ..usdc_wsol_pool()
+    };
+
+    assert_eq!(
+        quote(&fixture, WSOL_MINT, 1_000_000_000),
+        QuoteResult {
+            out_amount: 214_799_921,
+            fee: 2_000_000,
+        }
+    );
+}
+
+/// Vault totals, vault LP supplies, pool vault LP balances and vault token balances
+fn balances(quote_data: &QuoteData) -> [u64; 8] {
+    [
+        quote_data.vault_a.total_amount,
+        quote_data.vault_b.total_amount,
+        quote_data.vault_a_lp_mint_supply,
+        quote_data.vault_b_lp_mint_supply,
+        quote_data.pool_vault_a_lp_token.amount,
+        quote_data.pool_vault_b_lp_token.amount,
+        quote_data.vault_a_token.amount,
+        quote_data.vault_b_token.amount,
+    ]
+}
+
+fn protocol_fee(quote_data: &QuoteData, in_amount: u64) -> u64 {
+    let fees = quote_data.pool.fees;
+    let trade_fee = fees.trading_fee(in_amount.into()).unwrap();
+
+    fees.protocol_trading_fee(trade_fee)
+        .unwrap()
+        .try_into()
+        .unwrap()
+}
+
+#[test]
+fn apply_quote_moves_the_quoted_amounts() {
+    let quote_data = usdc_wsol_pool().snapshot().to_quote_data().unwrap();
+    let in_amount = 100_000_000;
+    let deposited = in_amount - protocol_fee(&quote_data, in_amount);
+    let [a_total, b_total, a_lp_supply, b_lp_supply, a_pool_lp, b_pool_lp, a_token, b_token] =
+        balances(&quote_data);
+
+    let (quote_result, next_quote_data) =
+        apply_quote(USDC_MINT, in_amount, quote_data.clone()).unwrap();
+    let [next_a_total, next_b_total, next_a_lp_supply, next_b_lp_supply, next_a_pool_lp, next_b_pool_lp, next_a_token, next_b_token] =
+        balances(&next_quote_data);
+
+    assert_eq!(
+        quote_result,
+        compute_quote(USDC_MINT, in_amount, quote_data).unwrap()
+    );
+    // The in token minus the protocol fee is deposited into the USDC vault
+    assert_eq!(next_a_total, a_total + deposited);
+    assert_eq!(next_a_token, a_token + deposited);
+    // The out token is withdrawn from the WSOL vault
+    assert_eq!(next_b_total, b_total - quote_result.out_amount);
+    assert_eq!(next_b_token, b_token - quote_result.out_amount);
+    // Vault LP is minted to and burnt from the pool only
+    let minted_lp = next_a_lp_supply - a_lp_supply;
+    let burnt_lp = b_lp_supply - next_b_lp_supply;
+    assert!(minted_lp > 0 && burnt_lp > 0);
+    assert_eq!(next_a_pool_lp, a_pool_lp + minted_lp);
+    assert_eq!(next_b_pool_lp, b_pool_lp - burnt_lp);
+}
+
+#[test]
+fn apply_trades_chains_mixed_directions() {
+    let quote_data = usdc_wsol_pool().snapshot().to_quote_data().unwrap();
+    let trades = [
+        Trade {
+            in_token_mint: USDC_MINT,
+            in_amount: 100_000_000,
+        },
+        Trade {
+            in_token_mint: WSOL_MINT,
+            in_amount: 2_000_000_000,
+        },
+        Trade {
+            in_token_mint: USDC_MINT,
+            in_amount: 50_000_000,
+        },
+    ];
+
+    let (quote_results, final_quote_data) = apply_trades(&trades, quote_data.clone()).unwrap();
+
+    let mut expected_quote_data = quote_data.clone();
+    for (trade, quote_result) in trades.iter().zip(&quote_results) {
+        let (expected_quote_result, next_quote_data) =
+            apply_quote(trade.in_token_mint, trade.in_amount, expected_quote_data).unwrap();
+        assert_eq!(*quote_result, expected_quote_result);
+        expected_quote_data = next_quote_data;
+    }
+    assert_eq!(quote_results.len(), trades.len());
+    assert_eq!(balances(&final_quote_data), balances(&expected_quote_data));
+    // After the USDC buy, WSOL is scarcer in the pool so selling it gets more USDC than on the
+    // initial state
+    assert!(
+        quote_results[1].out_amount
+            > compute_quote(WSOL_MINT, 2_000_000_000, quote_data)
+                .unwrap()
+                .out_amount
+    );
+}
+
+#[test]
+fn apply_trades_reports_the_failing_trade() {
+    let quote_data = usdc_wsol_pool().snapshot().to_quote_data().unwrap();
+    let trades = [
+        Trade {
+            in_token_mint: USDC_MINT,
+            in_amount: 100_000_000,
+        },
+        Trade {
+            in_token_mint: METAV_MINT,
+            in_amount: 100_000_000,
+        },
+    ];
+
+    let error = apply_trades(&trades, quote_data).err().unwrap();
+
+    assert_eq!(error.to_string(), "Fail to apply trade 1");
+}
+
+#[test]
+fn swap_is_synced_into_pools_sharing_a_vault() {
+    let usdc_wsol = usdc_wsol_pool().snapshot().to_quote_data().unwrap();
+    let mut metav_wsol = metav_wsol_pool().snapshot().to_quote_data().unwrap();
+    let metav_balances = balances(&metav_wsol);
+    assert_eq!(usdc_wsol.pool.b_vault, metav_wsol.pool.b_vault);
+
+    let (_, usdc_wsol) = apply_quote(USDC_MINT, 100_000_000, usdc_wsol).unwrap();
+    sync_shared_vaults(&usdc_wsol, &mut metav_wsol);
+
+    let [_, wsol_total, _, wsol_lp_supply, _, _, _, wsol_token] = balances(&usdc_wsol);
+    let [metav_total, _, metav_lp_supply, _, metav_pool_lp, pool_wsol_lp, metav_token, _] =
+        metav_balances;
+    // The WSOL vault follows the swap, the METAV vault and the pool's own LP are untouched
+    assert_eq!(
+        balances(&metav_wsol),
+        [
+            metav_total,
+            wsol_total,
+            metav_lp_supply,
+            wsol_lp_supply,
+            metav_pool_lp,
+            pool_wsol_lp,
+            metav_token,
+            wsol_token,
+        ]
+    );
+}
+
+fn not_activated_usdc_wsol_pool(whitelisted_vault: Pubkey) -> QuoteData {
+    let fixture = PoolFixture {
+        activation_type: ActivationType::Timestamp as u8,
+        activation_point: UNIX_TIMESTAMP as u64 + 60,
+        ..usdc_wsol_pool()
+    };
+    let mut quote_data = fixture.snapshot().to_quote_data().unwrap();
+    quote_data.pool.bootstrapping.whitelisted_vault = whitelisted_vault;
+
+    quote_data
+}
+
+#[test]
+fn whitelisted_vault_swaps_before_activation() {
+    let whitelisted_vault = Pubkey::new_unique();
+    let quote_data = not_activated_usdc_wsol_pool(whitelisted_vault);
+
+    assert_eq!(
+        compute_quote_for_user(
+            USDC_MINT,
+            100_000_000,
+            quote_data.clone(),
+            Some(whitelisted_vault)
+        )
+        .unwrap(),
+        quote(&usdc_wsol_pool(), USDC_MINT, 100_000_000)
+    );
+    assert!(compute_quote(USDC_MINT, 100_000_000, quote_data).is_err());
+}
+
+#[test]
+fn whitelisted_vault_applies_swaps_before_activation() {
+    let whitelisted_vault = Pubkey::new_unique();
+    let quote_data = not_activated_usdc_wsol_pool(whitelisted_vault);
+
+    let (quote_result, _) = apply_quote_for_user(
+        USDC_MINT,
+        100_000_000,
+        quote_data.clone(),
+        Some(whitelisted_vault),
+    )
+    .unwrap();
+
+    assert_eq!(
+        quote_result,
+        quote(&usdc_wsol_pool(), USDC_MINT, 100_000_000)
+    );
+    assert!(apply_quote(USDC_MINT, 100_000_000, quote_data).is_err());
+}
+
+#[test]
+fn other_users_cannot_swap_before_activation() {
+    let quote_data = not_activated_usdc_wsol_pool(Pubkey::new_unique());
+
+    assert!(compute_quote_for_user(
+        USDC_MINT,
+        100_000_000,
+        quote_data,
+        Some(Pubkey::new_unique())
+    )
+    .is_err());
+}
+
+#[test]
+fn pool_without_whitelisted_vault_rejects_every_user_before_activation() {
+    let quote_data = not_activated_usdc_wsol_pool(Pubkey::default());
+
+    assert!(
+        compute_quote_for_user(USDC_MINT, 100_000_000, quote_data, Some(Pubkey::default()))
+            .is_err()
+    );
+}
+
+#[test]
+fn legacy_vault_sell() {
+    assert_eq!(
+        quote(&legacy_vault_pool(), LEGACY_VAULT_TOKEN_MINT, 1_000_000_000),
+        QuoteResult {
+            out_amount: 1_043_631_551,
+            fee: 400_000,
+        }
+    );
+}
+
+#[test]
+fn legacy_vault_buy() {
+    assert_eq!(
+        quote(&legacy_vault_pool(), USDC_MINT, 1_000_000_000),
+        QuoteResult {
+            out_amount: 941_972_800,
+            fee: 400_000,
+        }
+    );
+}
+
+#[test]
+fn legacy_vault_lp_mint_is_resolved_from_state() {
+    let fixture = legacy_vault_pool();
+    let quote_data = fixture.snapshot().to_quote_data().unwrap();
+    let derived = get_all_accounts_for_quote(LEGACY_VAULT_TOKEN_MINT, USDC_MINT, LEGACY_VAULT_POOL);
+    let resolved =
+        get_accounts_from_state(&quote_data.pool, &quote_data.vault_a, &quote_data.vault_b);
+
+    assert_eq!(resolved.base_vault_lp_mint_address, LEGACY_VAULT_LP_MINT);
+    assert_ne!(derived.base_vault_lp_mint_address, LEGACY_VAULT_LP_MINT);
+    assert_eq!(
+        check_accounts_for_quote(&resolved, &derived).unwrap(),
+        LegacyLpMints {
+            vault_a: Some(LEGACY_VAULT_LP_MINT),
+            vault_b: None,
+        }
+    );
+}
+
+#[test]
+fn pda_lp_mints_are_not_reported_as_legacy() {
+    let quote_data = usdc_wsol_pool().snapshot().to_quote_data().unwrap();
+    let resolved =
+        get_accounts_from_state(&quote_data.pool, &quote_data.vault_a, &quote_data.vault_b);
+
+    assert_eq!(
+        check_accounts_for_quote(
+            &resolved,
+            &get_all_accounts_for_quote(USDC_MINT, WSOL_MINT, USDC_WSOL_POOL),
+        )
+        .unwrap(),
+        LegacyLpMints::default()
+    );
+}
+
+#[test]
+fn vault_not_matching_pda_is_rejected() {
+    let quote_data = usdc_wsol_pool().snapshot().to_quote_data().unwrap();
+    let mut resolved =
+        get_accounts_from_state(&quote_data.pool, &quote_data.vault_a, &quote_data.vault_b);
+    resolved.base_vault_authority = Pubkey::new_unique();
+
+    assert!(check_accounts_for_quote(
+        &resolved,
+        &get_all_accounts_for_quote(USDC_MINT, WSOL_MINT, USDC_WSOL_POOL),
+    )
+    .is_err());
+}
+
+#[test]
+fn pool_address_with_config_seeds_the_larger_mint_first() {
+    let (smaller_mint, larger_mint) = (
+        Pubkey::new_from_array([1; 32]),
+        Pubkey::new_from_array([2; 32]),
+    );
+    let config = Pubkey::new_unique();
+    let pool_address = |first: Pubkey, second: Pubkey| {
+        Pubkey::find_program_address(
+            &[first.as_ref(), second.as_ref(), config.as_ref()],
+            &METEORA_DYN_PROGRAM_ID,
+        )
+        .0
+    };
+
+    let expected = pool_address(larger_mint, smaller_mint);
+    assert_eq!(
+        derive_pool_address_with_config(smaller_mint, larger_mint, config),
+        expected
+    );
+    assert_eq!(
+        derive_pool_address_with_config(larger_mint, smaller_mint, config),
+        expected
+    );
+    assert_ne!(pool_address(smaller_mint, larger_mint), expected);
+}
+
+#[test]
+fn snapshot_json_round_trip() {
+    let snapshot = usdc_wsol_pool().snapshot();
+    let loaded = QuoteSnapshot::from_json(&snapshot.to_json().unwrap()).unwrap();
+
+    assert_eq!(loaded, snapshot);
+    assert_eq!(
+        compute_quote(WSOL_MINT, 1_000_000_000, loaded.to_quote_data().unwrap()).unwrap(),
+        quote(&usdc_wsol_pool(), WSOL_MINT, 1_000_000_000),
+    );
+}
+
+#[test]
+fn committed_fixtures_match_builders() {
+    // `fixtures/` holds the synthetic fixtures the benchmarks run on, keep it in sync with the
+    // fixture builders
+    for (name, fixture) in [
+        ("synthetic_usdc_wsol", usdc_wsol_pool()),
+        ("synthetic_metav_wsol", metav_wsol_pool()),
+    ] {
+        let path = format!("{}/fixtures/{}.json", env!("CARGO_MANIFEST_DIR"), name);
+        if std::env::var_os("UPDATE_FIXTURES").is_some() {
+            fixture.snapshot().save(&path).unwrap();
+        }
+
+        assert_eq!(QuoteSnapshot::load(&path).unwrap(), fixture.snapshot());
+    }
+}
+
+/// Mainnet swaps the quote is checked against, recorded in `fixtures/recorded/`. See "Recorded
+/// swaps" in the README for how to record them.
+const RECORDED_SWAPS: [&str; 4] = [
+    "metav_wsol",
+    "usdc_wsol",
+    "legacy_lp_mint_vault",
+    "locked_profit_vault",
+];
+
+#[test]
+#[ignore = "the mainnet recordings are not committed yet, see the README"]
+fn recorded_swaps_match_the_on_chain_amounts() {
+    for name in RECORDED_SWAPS {
+        let path = format!(
+            "{}/fixtures/recorded/{}.json",
+            env!("CARGO_MANIFEST_DIR"),
+            name
+        );
+        let swap = RecordedSwap::load(&path).unwrap();
+        let quote_data = swap.snapshot.to_quote_data().unwrap();
+
+        let quote = compute_quote(swap.in_token_mint, swap.in_amount, quote_data).unwrap();
+        assert_eq!(
+            quote.out_amount, swap.out_amount,
+            "{} ({})",
+            name, swap.signature
+        );
+    }
+}
+
+#[test]
+fn recorded_swap_json_round_trip() {
+    let snapshot = usdc_wsol_pool().snapshot();
+    let swap = RecordedSwap {
+        signature: "synthetic".to_string(),
+        in_token_mint: WSOL_MINT,
+        in_amount: 1_000_000_000,
+        out_amount: quote(&usdc_wsol_pool(), WSOL_MINT, 1_000_000_000).out_amount,
+        snapshot: snapshot.clone(),
+    };
+
+    assert_eq!(
+        RecordedSwap::from_json(&swap.to_json().unwrap()).unwrap(),
+        swap
+    );
+
+    let other_version = RecordedSwap {
+        snapshot: QuoteSnapshot {
+            version: QUOTE_SNAPSHOT_VERSION + 1,
+            ..snapshot
+        },
+        ..swap
+    };
+    assert!(RecordedSwap::from_json(&other_version.to_json().unwrap()).is_err());
+}
+
+#[test]
+fn snapshot_with_unknown_version_is_rejected() {
+    let snapshot = QuoteSnapshot {
+        version: QUOTE_SNAPSHOT_VERSION + 1,
+        ..usdc_wsol_pool().snapshot()
+    };
+
+    assert!(QuoteSnapshot::from_json(&snapshot.to_json().unwrap()).is_err());
+}
+
+#[test]
+fn snapshot_with_swapped_accounts_is_rejected() {
+    let snapshot = usdc_wsol_pool().snapshot();
+    let snapshot = QuoteSnapshot {
+        vault_a_token: snapshot.vault_b_token.clone(),
+        ..snapshot
+    };
+
+    assert!(snapshot.to_quote_data().is_err());
+}
+
+#[test]
+fn snapshot_reads_the_pool_lp_supply() {
+    let snapshot = usdc_wsol_pool().snapshot();
+
+    assert_eq!(snapshot.pool_lp_supply().unwrap(), POOL_LP_SUPPLY);
+
+    // The vault A LP mint is a mint too, but not the one of the pool
+    let snapshot = QuoteSnapshot {
+        pool_lp_mint: snapshot.vault_a_lp_mint.clone(),
+        ..snapshot
+    };
+    assert!(snapshot.pool_lp_supply().is_err());
+    assert!(snapshot.to_quote_data().is_err());
+}
+
+proptest! {
+    #[test]
+    fn quote_never_decreases_k(in_amount in 1..100_000_000_000_000u64, a_to_b in any::<bool>()) {
+        let quote_data = usdc_wsol_pool().snapshot().to_quote_data().unwrap();
+        let in_token_mint = if a_to_b { USDC_MINT } else { WSOL_MINT };
+        let (token_a_amount, token_b_amount) = get_pool_reserves(&quote_data).unwrap();
+
+        if let Ok((_, quote_data)) = apply_quote(in_token_mint, in_amount, quote_data) {
+            let (new_token_a_amount, new_token_b_amount) = get_pool_reserves(&quote_data).unwrap();
+            prop_assert!(
+                u128::from(new_token_a_amount) * u128::from(new_token_b_amount)
+                    >= u128::from(token_a_amount) * u128::from(token_b_amount)
+            );
+        }
+    }
+
+    #[test]
+    fn quote_round_trip_never_profits(in_amount in 1..100_000_000_000_000u64) {
+        let quote_data = usdc_wsol_pool().snapshot().to_quote_data().unwrap();
+
+        if let Ok((forward, quote_data)) = apply_quote(WSOL_MINT, in_amount, quote_data) {
+            if let Ok(backward) = compute_quote(USDC_MINT, forward.out_amount, quote_data) {
+                prop_assert!(backward.out_amount <= in_amount);
+            }
+        }
+    }
+}
@@ -0,0 +1,235 @@
+use crate::quote_service::{apply_quote, sync_shared_vaults, QuoteData, QuoteResult};
+use anyhow::{ensure, Context};
+use solana_program::pubkey::Pubkey;
+use std::collections::HashMap;
+
+/// Swap through one pool of a route
+#[derive(Debug, Clone)]
+pub struct RouteHop {
+    /// Pool address
+    pub pool: Pubkey,
+    /// Mint of the token swapped into the pool
+    pub in_token_mint: Pubkey,
+    /// Mint of the token received from the pool
+    pub out_token_mint: Pubkey,
+    /// Swap in amount
+    pub in_amount: u64,
+    /// Swap out amount
+    pub out_amount: u64,
+    /// Trade fee amount. Fee is charged based on in token mint.
+    pub fee: u64,
+}
+
+/// Chain of swaps from the in token to the out token
+#[derive(Debug, Clone)]
+pub struct Route {
+    /// Swaps in order, the out amount of a hop is the in amount of the next one
+    pub hops: Vec<RouteHop>,
+    /// Swap in amount of the first hop
+    pub in_amount: u64,
+    /// Swap out amount of the last hop
+    pub out_amount: u64,
+}
+
+/// Route being searched, with the state of each pool after its hop
+#[derive(Clone)]
+struct SearchRoute {
+    route: Route,
+    hop_states: Vec<QuoteData>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct RouteKey {
+    in_token_mint: Pubkey,
+    out_token_mint: Pubkey,
+    in_amount: u64,
+    max_hops: usize,
+}
+
+/// Router over a token graph where nodes are mints and edges are pools
+pub struct Router {
+    /// Pools of the snapshot
+    pools: Vec<(Pubkey, QuoteData)>,
+    /// Indexes of the pools trading each mint
+    edges: HashMap<Pubkey, Vec<usize>>,
+    /// Slot of the snapshot, the latest slot among the pools
+    slot: u64,
+    /// Best routes found on the snapshot
+    cache: HashMap<RouteKey, Option<Route>>,
+}
+
+impl Router {
+    pub fn new(pools: Vec<(Pubkey, QuoteData)>) -> Self {
+        let mut router = Self {
+            pools: vec![],
+            edges: HashMap::new(),
+            slot: 0,
+            cache: HashMap::new(),
+        };
+        router.update(pools);
+        router
+    }
+
+    /// Replace the snapshot and drop the cached routes, which were found on the previous one
+    pub fn update(&mut self, pools: Vec<(Pubkey, QuoteData)>) {
+        let slot = pools
+            .iter()
+            .map(|(_, quote_data)| quote_data.clock.slot)
+            .max()
+            .unwrap_or_default();
+        self.cache.clear();
+
+        let mut edges: HashMap<Pubkey, Vec<usize>> = HashMap::new();
+        for (index, (_, quote_data)) in pools.iter().enumerate() {
+            edges
+                .entry(quote_data.pool.token_a_mint)
+                .or_default()
+                .push(index);
+            edges
+                .entry(quote_data.pool.token_b_mint)
+                .or_default()
+                .push(index);
+        }
+
+        self.pools = pools;
+        self.edges = edges;
+        self.slot = slot;
+    }
+
+    /// Slot of the snapshot
+    pub fn slot(&self) -> u64 {
+        self.slot
+    }
+
+    /// Find the route with the highest output of at most `max_hops` swaps. A pool is used at most
+    /// once per route, and every hop is quoted with `apply_quote` on the vault state left by the
+    /// previous hops, so pools sharing a vault see the deposits and withdrawals of each other.
+    pub fn find_best_route(
+        &mut self,
+        in_token_mint: Pubkey,
+        out_token_mint: Pubkey,
+        in_amount: u64,
+        max_hops: usize,
+    ) -> anyhow::Result<Route> {
+        ensure!(in_token_mint != out_token_mint, "Same in and out token");
+        ensure!(max_hops > 0, "Max hops must be positive");
+
+        let key = RouteKey {
+            in_token_mint,
+            out_token_mint,
+            in_amount,
+            max_hops,
+        };
+        let route = match self.cache.get(&key) {
+            Some(route) => route.clone(),
+            None => {
+                let route = self.search(in_token_mint, out_token_mint, in_amount, max_hops);
+                self.cache.insert(key, route.clone());
+                route
+            }
+        };
+
+        route.context("No route found")
+    }
+
+    /// Layered search: after `n` rounds, `best` holds for every mint the route of at most `n`
+    /// hops with the highest amount. More input never gives less output, so extending the best
+    /// route of each mint is enough. Each round only extends the routes of the previous round,
+    /// so a route improved during a round is not extended again in the same round.
+    fn search(
+        &self,
+        in_token_mint: Pubkey,
+        out_token_mint: Pubkey,
+        in_amount: u64,
+        max_hops: usize,
+    ) -> Option<Route> {
+        let mut best: HashMap<Pubkey, SearchRoute> = HashMap::from([(
+            in_token_mint,
+            SearchRoute {
+                route: Route {
+                    hops: vec![],
+                    in_amount,
+                    out_amount: in_amount,
+                },
+                hop_states: vec![],
+            },
+        )]);
+        let mut frontier = vec![in_token_mint];
+
+        for _ in 0..max_hops {
+            let previous = best.clone();
+            let mut next_frontier = vec![];
+
+            for mint in frontier {
+                let SearchRoute { route, hop_states } = &previous[&mint];
+                for &index in self.edges.get(&mint).into_iter().flatten() {
+                    let (pool_address, quote_data) = &self.pools[index];
+                    if route.hops.iter().any(|hop| hop.pool == *pool_address) {
+                        continue;
+                    }
+
+                    let next_mint = if quote_data.pool.token_a_mint == mint {
+                        quote_data.pool.token_b_mint
+                    } else {
+                        quote_data.pool.token_a_mint
+                    };
+                    // Routes never pass through the in token again
+                    if next_mint == in_token_mint {
+                        continue;
+                    }
+
+                    let mut quote_data = quote_data.clone();
+                    for hop_state in hop_states {
+                        sync_shared_vaults(hop_state, &mut quote_data);
+                    }
+                    let Ok((QuoteResult { out_amount, fee }, quote_data)) =
+                        apply_quote(mint, route.out_amount, quote_data)
+                    else {
+                        continue;
+                    };
+
+                    if best
+                        .get(&next_mint)
+                        .is_some_and(|next_route| next_route.route.out_amount >= out_amount)
+                    {
+                        continue;
+                    }
+
+                    let mut hops = route.hops.clone();
+                    hops.push(RouteHop {
+                        pool: *pool_address,
+                        in_token_mint: mint,
+                        out_token_mint: next_mint,
+                        in_amount: route.out_amount,
+                        out_amount,
+                        fee,
+                    });
+                    let mut next_hop_states = hop_states.clone();
+                    next_hop_states.push(quote_data);
+                    best.insert(
+                        next_mint,
+                        SearchRoute {
+                            route: Route {
+                                hops,
+                                in_amount,
+                                out_amount,
+                            },
+                            hop_states: next_hop_states,
+                        },
+                    );
+                    if next_mint != out_token_mint && !next_frontier.contains(&next_mint) {
+                        next_frontier.push(next_mint);
+                    }
+                }
+            }
+
+            frontier = next_frontier;
+        }
+
+        best.remove(&out_token_mint)
+            .map(|search_route| search_route.route)
+    }
+}
+
+#[cfg(test)]
+mod tests;
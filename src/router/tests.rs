@@ -0,0 +1,188 @@
+use super::*;
+use crate::quote_service::{apply_quote, compute_quote, sync_shared_vaults};
+use crate::test_fixtures::*;
+
+const DEEP: u64 = 1_000_000_000_000;
+const SHALLOW: u64 = 1_000_000_000;
+
+struct Graph {
+    in_mint: Pubkey,
+    a_mint: Pubkey,
+    b_mint: Pubkey,
+    out_mint: Pubkey,
+    in_a: (Pubkey, QuoteData),
+    in_b_shallow: (Pubkey, QuoteData),
+    a_b: (Pubkey, QuoteData),
+    b_out: (Pubkey, QuoteData),
+}
+
+fn pool(token_a: (Pubkey, u64), token_b: (Pubkey, u64)) -> (Pubkey, QuoteData) {
+    let fixture = token_pool(Pubkey::new_unique(), token_a, token_b);
+
+    (
+        fixture.pool_address,
+        fixture.snapshot().to_quote_data().unwrap(),
+    )
+}
+
+/// IN-A, A-B and B-OUT are deep pools, IN-B a shallow one, all at the same price. The best way
+/// to B is through A, but the only route of two hops to OUT goes through the shallow pool.
+fn graph() -> Graph {
+    let [in_mint, a_mint, b_mint, out_mint] = [(); 4].map(|_| Pubkey::new_unique());
+
+    Graph {
+        in_mint,
+        a_mint,
+        b_mint,
+        out_mint,
+        in_a: pool((in_mint, DEEP), (a_mint, DEEP)),
+        in_b_shallow: pool((in_mint, SHALLOW), (b_mint, SHALLOW)),
+        a_b: pool((a_mint, DEEP), (b_mint, DEEP)),
+        b_out: pool((b_mint, DEEP), (out_mint, DEEP)),
+    }
+}
+
+fn router(graph: &Graph) -> Router {
+    Router::new(vec![
+        graph.in_a.clone(),
+        graph.in_b_shallow.clone(),
+        graph.a_b.clone(),
+        graph.b_out.clone(),
+    ])
+}
+
+fn hop_pools(route: &Route) -> Vec<Pubkey> {
+    route.hops.iter().map(|hop| hop.pool).collect()
+}
+
+fn assert_hops_chain(route: &Route) {
+    assert_eq!(route.hops[0].in_amount, route.in_amount);
+    assert_eq!(route.hops.last().unwrap().out_amount, route.out_amount);
+    for hops in route.hops.windows(2) {
+        assert_eq!(hops[0].out_token_mint, hops[1].in_token_mint);
+        assert_eq!(hops[0].out_amount, hops[1].in_amount);
+    }
+}
+
+#[test]
+fn route_never_exceeds_max_hops() {
+    let graph = graph();
+    let mut router = router(&graph);
+
+    // The route to B improved through A in the second round must not be extended to OUT in the
+    // same round
+    let route = router
+        .find_best_route(graph.in_mint, graph.out_mint, SHALLOW, 2)
+        .unwrap();
+
+    assert_eq!(hop_pools(&route), vec![graph.in_b_shallow.0, graph.b_out.0]);
+    assert_hops_chain(&route);
+}
+
+#[test]
+fn longer_route_is_found_with_more_hops() {
+    let graph = graph();
+    let mut router = router(&graph);
+
+    let two_hops = router
+        .find_best_route(graph.in_mint, graph.out_mint, SHALLOW, 2)
+        .unwrap();
+    let three_hops = router
+        .find_best_route(graph.in_mint, graph.out_mint, SHALLOW, 3)
+        .unwrap();
+
+    assert_eq!(
+        hop_pools(&three_hops),
+        vec![graph.in_a.0, graph.a_b.0, graph.b_out.0]
+    );
+    assert!(three_hops.out_amount > two_hops.out_amount);
+    assert_hops_chain(&three_hops);
+}
+
+#[test]
+fn route_amounts_match_the_hop_quotes() {
+    let graph = graph();
+    let mut router = router(&graph);
+
+    let route = router
+        .find_best_route(graph.in_mint, graph.b_mint, SHALLOW, 2)
+        .unwrap();
+
+    // IN-A and A-B share the A vault, so the second hop is quoted on the vault left by the first
+    let (a_quote, in_a_quote_data) =
+        apply_quote(graph.in_mint, SHALLOW, graph.in_a.1.clone()).unwrap();
+    let mut a_b_quote_data = graph.a_b.1.clone();
+    sync_shared_vaults(&in_a_quote_data, &mut a_b_quote_data);
+    let b_amount = compute_quote(graph.a_mint, a_quote.out_amount, a_b_quote_data)
+        .unwrap()
+        .out_amount;
+    assert_eq!(hop_pools(&route), vec![graph.in_a.0, graph.a_b.0]);
+    assert_eq!(route.out_amount, b_amount);
+}
+
+#[test]
+fn route_hops_carry_the_shared_vault_state() {
+    let graph = graph();
+    let mut router = router(&graph);
+
+    let route = router
+        .find_best_route(graph.in_mint, graph.out_mint, SHALLOW, 3)
+        .unwrap();
+
+    // Same result as quoting the hops as a cycle would, each on the vaults left by the previous
+    // ones
+    let mut amount = SHALLOW;
+    let mut states: Vec<QuoteData> = vec![];
+    for hop in &route.hops {
+        let (_, quote_data) = [&graph.in_a, &graph.a_b, &graph.b_out]
+            .into_iter()
+            .find(|(pool, _)| *pool == hop.pool)
+            .unwrap();
+        let mut quote_data = quote_data.clone();
+        for state in &states {
+            sync_shared_vaults(state, &mut quote_data);
+        }
+        let (quote, quote_data) = apply_quote(hop.in_token_mint, amount, quote_data).unwrap();
+        assert_eq!(hop.out_amount, quote.out_amount);
+        amount = quote.out_amount;
+        states.push(quote_data);
+    }
+    assert_eq!(route.out_amount, amount);
+}
+
+#[test]
+fn update_drops_the_cached_routes() {
+    let graph = graph();
+    let mut router = router(&graph);
+    router
+        .find_best_route(graph.in_mint, graph.out_mint, SHALLOW, 3)
+        .unwrap();
+
+    // Same slot, but the pool to OUT is gone
+    router.update(vec![
+        graph.in_a.clone(),
+        graph.in_b_shallow.clone(),
+        graph.a_b.clone(),
+    ]);
+
+    assert_eq!(router.slot(), SLOT);
+    assert!(router
+        .find_best_route(graph.in_mint, graph.out_mint, SHALLOW, 3)
+        .is_err());
+}
+
+#[test]
+fn invalid_searches_are_rejected() {
+    let graph = graph();
+    let mut router = router(&graph);
+
+    assert!(router
+        .find_best_route(graph.in_mint, graph.in_mint, SHALLOW, 2)
+        .is_err());
+    assert!(router
+        .find_best_route(graph.in_mint, graph.out_mint, SHALLOW, 0)
+        .is_err());
+    assert!(router
+        .find_best_route(graph.in_mint, Pubkey::new_unique(), SHALLOW, 3)
+        .is_err());
+}
@@ -1,8 +1,23 @@
-use anchor_lang::AccountDeserialize;
+use crate::decoder::{decode_mint, decode_token_account};
 use anchor_spl::token::{Mint, TokenAccount};
+use anyhow::{bail, ensure, Context};
+use solana_account::Account;
+use solana_account_decoder_client_types::UiAccountEncoding;
 use solana_client::rpc_client::RpcClient;
-use solana_program::clock::Slot;
+use solana_client::rpc_config::{
+    RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcTokenAccountsFilter,
+};
+use solana_client::rpc_filter::RpcFilterType;
+use solana_client::rpc_request::RpcRequest;
+use solana_client::rpc_response::{Response, RpcKeyedAccount};
 use solana_program::pubkey::Pubkey;
+use std::str::FromStr;
+
+/// Most accounts `getMultipleAccounts` returns in one call
+pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// Most reads of the chunks of `RpcService::get_multiple_accounts` until they share a slot
+pub const MAX_SAME_SLOT_ATTEMPTS: usize = 3;
 
 pub struct RpcService {
     pub rpc_client: RpcClient,
@@ -15,27 +30,146 @@ impl RpcService {
         }
     }
 
-    pub fn get_slot(&self) -> Slot {
-        self.rpc_client.get_slot().unwrap()
+    pub fn get_mint(&self, pubkey: &Pubkey) -> anyhow::Result<Mint> {
+        decode_mint(&self.get_account_data(pubkey)?)
     }
 
-    pub fn get_block_time(&self, slot: Slot) -> i64 {
-        self.rpc_client.get_block_time(slot).unwrap()
+    pub fn get_token_account(&self, pubkey: &Pubkey) -> anyhow::Result<TokenAccount> {
+        decode_token_account(&self.get_account_data(pubkey)?)
     }
 
-    pub fn get_mint(&self, pubkey: &Pubkey) -> Mint {
-        Mint::try_deserialize_unchecked(&mut &self.rpc_client.get_account_data(pubkey).unwrap()[..])
-            .unwrap()
+    pub fn get_account_data(&self, pubkey: &Pubkey) -> anyhow::Result<Vec<u8>> {
+        self.rpc_client
+            .get_account_data(pubkey)
+            .with_context(|| format!("Fail to get account {}", pubkey))
     }
 
-    pub fn get_token_account(&self, pubkey: &Pubkey) -> TokenAccount {
-        TokenAccount::try_deserialize_unchecked(
-            &mut &self.rpc_client.get_account_data(pubkey).unwrap()[..],
-        )
-        .unwrap()
+    /// Read the accounts from the same slot, in chunks of `MAX_MULTIPLE_ACCOUNTS`, see
+    /// `read_at_same_slot`
+    pub fn get_multiple_accounts(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> anyhow::Result<Vec<Option<Account>>> {
+        read_at_same_slot(pubkeys, |chunk, min_context_slot| {
+            self.rpc_client
+                .get_multiple_accounts_with_config(
+                    chunk,
+                    RpcAccountInfoConfig {
+                        encoding: Some(UiAccountEncoding::Base64),
+                        min_context_slot,
+                        ..RpcAccountInfoConfig::default()
+                    },
+                )
+                .context("Fail to get multiple accounts")
+        })
     }
 
-    pub fn get_account_data(&self, pubkey: &Pubkey) -> Vec<u8> {
-        self.rpc_client.get_account_data(pubkey).unwrap()
+    pub fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        filters: Vec<RpcFilterType>,
+    ) -> anyhow::Result<Vec<(Pubkey, Account)>> {
+        self.rpc_client
+            .get_program_accounts_with_config(
+                program_id,
+                RpcProgramAccountsConfig {
+                    filters: Some(filters),
+                    account_config: RpcAccountInfoConfig {
+                        encoding: Some(UiAccountEncoding::Base64),
+                        ..RpcAccountInfoConfig::default()
+                    },
+                    ..RpcProgramAccountsConfig::default()
+                },
+            )
+            .with_context(|| format!("Fail to get program accounts of {}", program_id))
+    }
+
+    /// Read the token accounts of the owner under the token program. RPC nodes do not serve
+    /// `getProgramAccounts` over the token programs, so token accounts are found by owner.
+    pub fn get_token_accounts_by_owner(
+        &self,
+        owner: &Pubkey,
+        token_program: &Pubkey,
+    ) -> anyhow::Result<Vec<(Pubkey, Account)>> {
+        // `RpcClient::get_token_accounts_by_owner` asks for parsed accounts, the raw data is
+        // requested here instead
+        let Response { value, .. }: Response<Vec<RpcKeyedAccount>> = self
+            .rpc_client
+            .send(
+                RpcRequest::GetTokenAccountsByOwner,
+                serde_json::json!([
+                    owner.to_string(),
+                    RpcTokenAccountsFilter::ProgramId(token_program.to_string()),
+                    RpcAccountInfoConfig {
+                        encoding: Some(UiAccountEncoding::Base64),
+                        ..RpcAccountInfoConfig::default()
+                    },
+                ]),
+            )
+            .with_context(|| format!("Fail to get token accounts of {}", owner))?;
+
+        value
+            .into_iter()
+            .map(|RpcKeyedAccount { pubkey, account }| {
+                let address = Pubkey::from_str(&pubkey)
+                    .with_context(|| format!("Invalid token account address {}", pubkey))?;
+                let account = account
+                    .decode()
+                    .with_context(|| format!("Fail to decode token account {}", address))?;
+                Ok((address, account))
+            })
+            .collect()
     }
 }
+
+/// Read the accounts in chunks of `MAX_MULTIPLE_ACCOUNTS`, all sent at once. A single call is
+/// served from one slot, but chunks may not be: when they come from different slots, they are
+/// read again no earlier than the latest of them, up to `MAX_SAME_SLOT_ATTEMPTS` times.
+fn read_at_same_slot(
+    pubkeys: &[Pubkey],
+    read_chunk: impl Fn(&[Pubkey], Option<u64>) -> anyhow::Result<Response<Vec<Option<Account>>>> + Sync,
+) -> anyhow::Result<Vec<Option<Account>>> {
+    let mut min_context_slot = None;
+
+    for _ in 0..MAX_SAME_SLOT_ATTEMPTS {
+        let responses = std::thread::scope(|scope| {
+            pubkeys
+                .chunks(MAX_MULTIPLE_ACCOUNTS)
+                .map(|chunk| scope.spawn(|| read_chunk(chunk, min_context_slot)))
+                .collect::<Vec<_>>()
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()
+        })?;
+
+        let slots = responses.iter().map(|response| response.context.slot);
+        let (first_slot, last_slot) = (slots.clone().min(), slots.max());
+        if first_slot == last_slot {
+            let mut accounts = Vec::with_capacity(pubkeys.len());
+            for (chunk, response) in pubkeys.chunks(MAX_MULTIPLE_ACCOUNTS).zip(responses) {
+                ensure!(
+                    response.value.len() == chunk.len(),
+                    "Got {} accounts for {} addresses",
+                    response.value.len(),
+                    chunk.len()
+                );
+                accounts.extend(response.value);
+            }
+            return Ok(accounts);
+        }
+        min_context_slot = last_slot;
+    }
+
+    bail!(
+        "Fail to read {} accounts at the same slot in {} attempts",
+        pubkeys.len(),
+        MAX_SAME_SLOT_ATTEMPTS
+    )
+}
+
+#[cfg(test)]
+mod tests;
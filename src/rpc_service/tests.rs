@@ -0,0 +1,115 @@
+use super::*;
+use solana_client::rpc_response::RpcResponseContext;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+fn account(lamports: u64) -> Account {
+    Account {
+        lamports,
+        ..Account::default()
+    }
+}
+
+fn response(slot: u64, value: Vec<Option<Account>>) -> Response<Vec<Option<Account>>> {
+    Response {
+        context: RpcResponseContext {
+            slot,
+            api_version: None,
+        },
+        value,
+    }
+}
+
+/// Addresses whose first byte is their index, so a chunk knows which accounts it holds
+fn addresses(count: usize) -> Vec<Pubkey> {
+    (0..count)
+        .map(|index| Pubkey::new_from_array([index as u8; 32]))
+        .collect()
+}
+
+fn accounts_of(chunk: &[Pubkey]) -> Vec<Option<Account>> {
+    chunk
+        .iter()
+        .map(|address| Some(account(address.to_bytes()[0] as u64)))
+        .collect()
+}
+
+#[test]
+fn chunks_are_read_in_order() {
+    let pubkeys = addresses(250);
+    let calls = AtomicUsize::new(0);
+
+    let accounts = read_at_same_slot(&pubkeys, |chunk, min_context_slot| {
+        assert!(chunk.len() <= MAX_MULTIPLE_ACCOUNTS);
+        assert_eq!(min_context_slot, None);
+        calls.fetch_add(1, Ordering::Relaxed);
+        Ok(response(10, accounts_of(chunk)))
+    })
+    .unwrap();
+
+    assert_eq!(calls.into_inner(), 3);
+    let lamports: Vec<u64> = accounts
+        .into_iter()
+        .map(|account| account.unwrap().lamports)
+        .collect();
+    assert_eq!(lamports, (0..250).collect::<Vec<u64>>());
+}
+
+#[test]
+fn chunks_from_different_slots_are_read_again_at_the_latest() {
+    let pubkeys = addresses(150);
+    let min_context_slots = Mutex::new(vec![]);
+
+    let accounts = read_at_same_slot(&pubkeys, |chunk, min_context_slot| {
+        min_context_slots.lock().unwrap().push(min_context_slot);
+        // The second chunk is served by a node one slot ahead on the first attempt
+        let slot = match (min_context_slot, chunk[0] == pubkeys[0]) {
+            (None, true) => 10,
+            _ => 11,
+        };
+        Ok(response(slot, accounts_of(chunk)))
+    })
+    .unwrap();
+
+    assert_eq!(accounts.len(), 150);
+    let mut min_context_slots = min_context_slots.into_inner().unwrap();
+    min_context_slots.sort();
+    assert_eq!(min_context_slots, vec![None, None, Some(11), Some(11)]);
+}
+
+#[test]
+fn chunks_never_at_the_same_slot_are_rejected() {
+    let pubkeys = addresses(150);
+    let calls = AtomicUsize::new(0);
+
+    let result = read_at_same_slot(&pubkeys, |chunk, _| {
+        let call = calls.fetch_add(1, Ordering::Relaxed) as u64;
+        Ok(response(call, accounts_of(chunk)))
+    });
+
+    assert!(result.is_err());
+    assert_eq!(calls.into_inner(), 2 * MAX_SAME_SLOT_ATTEMPTS);
+}
+
+#[test]
+fn missing_accounts_and_failed_chunks() {
+    let pubkeys = addresses(150);
+
+    // Accounts that do not exist are returned as `None`, a short response is an error
+    let accounts = read_at_same_slot(&pubkeys, |chunk, _| {
+        Ok(response(10, vec![None; chunk.len()]))
+    })
+    .unwrap();
+    assert!(accounts.iter().all(Option::is_none));
+    assert!(read_at_same_slot(&pubkeys, |_, _| Ok(response(10, vec![]))).is_err());
+
+    assert!(read_at_same_slot(&pubkeys, |chunk, _| {
+        ensure!(chunk[0] == pubkeys[0], "Node unavailable");
+        Ok(response(10, accounts_of(chunk)))
+    })
+    .is_err());
+
+    assert!(read_at_same_slot(&[], |_, _| unreachable!())
+        .unwrap()
+        .is_empty());
+}
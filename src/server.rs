@@ -0,0 +1,82 @@
+use crate::quote_service::{QuoteResult, QuoteService};
+use serde::{Deserialize, Serialize};
+use solana_client::client_error::ClientError;
+use solana_program::pubkey::Pubkey;
+use std::str::FromStr;
+use std::sync::Arc;
+use warp::http::StatusCode;
+use warp::Filter;
+
+#[derive(Debug, Deserialize)]
+pub struct QuoteQuery {
+    /// Pool to quote against
+    pub pool: String,
+    /// Mint of the token swapped into the pool
+    pub in_mint: String,
+    /// Amount of the in token
+    pub amount: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+/// Routes of the quote server: `GET /quote?pool=&in_mint=&amount=`
+pub fn routes(
+    quote_service: Arc<QuoteService>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::get()
+        .and(warp::path("quote"))
+        .and(warp::path::end())
+        .and(warp::query::<QuoteQuery>())
+        .and(warp::any().map(move || quote_service.clone()))
+        .and_then(handle_quote)
+}
+
+async fn handle_quote(
+    query: QuoteQuery,
+    quote_service: Arc<QuoteService>,
+) -> Result<warp::reply::WithStatus<warp::reply::Json>, warp::Rejection> {
+    // The RPC client is blocking, keep it off the async workers
+    let result = tokio::task::spawn_blocking(move || quote(&quote_service, &query))
+        .await
+        .map_err(anyhow::Error::from)
+        .and_then(|result| result);
+
+    Ok(match result {
+        Ok(quote_result) => {
+            warp::reply::with_status(warp::reply::json(&quote_result), StatusCode::OK)
+        }
+        Err(err) => warp::reply::with_status(
+            warp::reply::json(&ErrorBody {
+                error: format!("{:#}", err),
+            }),
+            error_status(&err),
+        ),
+    })
+}
+
+/// 502 when the RPC failed, 500 when the quote task panicked, and 400 when the query can not be
+/// quoted
+fn error_status(err: &anyhow::Error) -> StatusCode {
+    if err.chain().any(|cause| cause.is::<ClientError>()) {
+        StatusCode::BAD_GATEWAY
+    } else if err.is::<tokio::task::JoinError>() {
+        StatusCode::INTERNAL_SERVER_ERROR
+    } else {
+        StatusCode::BAD_REQUEST
+    }
+}
+
+fn quote(quote_service: &QuoteService, query: &QuoteQuery) -> anyhow::Result<QuoteResult> {
+    let pool = Pubkey::from_str(&query.pool)
+        .map_err(|_| anyhow::anyhow!("Invalid pool address: {}", query.pool))?;
+    let in_mint = Pubkey::from_str(&query.in_mint)
+        .map_err(|_| anyhow::anyhow!("Invalid in mint: {}", query.in_mint))?;
+
+    quote_service.get_for_pair(in_mint, pool, query.amount)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,53 @@
+use super::*;
+use crate::rpc_service::RpcService;
+
+/// Quote service whose RPC refuses every connection
+fn unreachable_quote_service() -> Arc<QuoteService> {
+    Arc::new(QuoteService::new(RpcService::new(
+        "http://127.0.0.1:1".to_string(),
+    )))
+}
+
+async fn get_quote(path: &str) -> StatusCode {
+    warp::test::request()
+        .path(path)
+        .reply(&routes(unreachable_quote_service()))
+        .await
+        .status()
+}
+
+#[tokio::test]
+async fn invalid_query_is_a_bad_request() {
+    let in_mint = Pubkey::new_unique();
+
+    assert_eq!(
+        get_quote(&format!("/quote?pool=invalid&in_mint={}&amount=1", in_mint)).await,
+        StatusCode::BAD_REQUEST
+    );
+}
+
+#[tokio::test]
+async fn rpc_failure_is_a_bad_gateway() {
+    let (pool, in_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    assert_eq!(
+        get_quote(&format!(
+            "/quote?pool={}&in_mint={}&amount=1",
+            pool, in_mint
+        ))
+        .await,
+        StatusCode::BAD_GATEWAY
+    );
+}
+
+#[tokio::test]
+async fn panicked_quote_task_is_an_internal_error() {
+    let join_error = tokio::task::spawn_blocking(|| panic!("quote task panicked"))
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        error_status(&anyhow::Error::from(join_error)),
+        StatusCode::INTERNAL_SERVER_ERROR
+    );
+}
@@ -0,0 +1,408 @@
+use crate::decoder::*;
+use crate::lp_analytics::PoolLpSnapshot;
+use crate::quote_service::{get_accounts_from_state, MeteoraAccounts, QuoteData};
+use crate::vault_quote::VaultQuoteData;
+use anyhow::{ensure, Context};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use serde::{Deserialize, Serialize};
+use solana_account::Account;
+use solana_program::pubkey::Pubkey;
+use solana_program::sysvar;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Version of the snapshot format. Bump it whenever the layout of `QuoteSnapshot` changes.
+pub const QUOTE_SNAPSHOT_VERSION: u32 = 2;
+
+/// Version of the vault snapshot format. Bump it whenever the layout of `VaultSnapshot` changes.
+pub const VAULT_SNAPSHOT_VERSION: u32 = 1;
+
+/// Raw on-chain account, as returned by the RPC
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotAccount {
+    /// Address of the account
+    #[serde(with = "pubkey_string")]
+    pub address: Pubkey,
+    /// Program owning the account
+    #[serde(with = "pubkey_string")]
+    pub owner: Pubkey,
+    /// Lamports held by the account
+    pub lamports: u64,
+    /// Raw account data, base64 encoded in the file
+    #[serde(with = "base64_bytes")]
+    pub data: Vec<u8>,
+}
+
+impl SnapshotAccount {
+    pub fn new(address: Pubkey, account: Account) -> Self {
+        Self {
+            address,
+            owner: account.owner,
+            lamports: account.lamports,
+            data: account.data,
+        }
+    }
+}
+
+/// Every account needed to quote a swap against a pool, read in one batch at one slot. A
+/// snapshot is enough to rebuild the quote data, so it can be quoted again without any RPC.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuoteSnapshot {
+    /// Version of the snapshot format, see `QUOTE_SNAPSHOT_VERSION`
+    pub version: u32,
+    /// Slot of the clock the accounts were read with
+    pub slot: u64,
+    pub clock: SnapshotAccount,
+    pub pool: SnapshotAccount,
+    /// LP mint of the pool, its supply values the LP positions of the pool
+    pub pool_lp_mint: SnapshotAccount,
+    pub vault_a: SnapshotAccount,
+    pub vault_b: SnapshotAccount,
+    pub vault_a_lp_mint: SnapshotAccount,
+    pub vault_b_lp_mint: SnapshotAccount,
+    pub pool_vault_a_lp_token: SnapshotAccount,
+    pub pool_vault_b_lp_token: SnapshotAccount,
+    pub vault_a_token: SnapshotAccount,
+    pub vault_b_token: SnapshotAccount,
+}
+
+impl QuoteSnapshot {
+    /// Decode the snapshot accounts into quote data. The vault, LP and token account addresses
+    /// must be the ones the pool and vault states point to.
+    pub fn to_quote_data(&self) -> anyhow::Result<QuoteData> {
+        ensure!(
+            self.clock.address == sysvar::clock::ID,
+            "Clock account is not the clock sysvar"
+        );
+        let clock = decode_clock(&self.clock.data)?;
+        ensure!(
+            clock.slot == self.slot,
+            "Snapshot slot does not match the clock"
+        );
+
+        let pool = decode_pool(&self.pool.data)?;
+        let curve_type = decode_curve_type(&self.pool.data)?;
+
+        let vault_a = decode_vault(&self.vault_a.data)?;
+        let vault_b = decode_vault(&self.vault_b.data)?;
+
+        let MeteoraAccounts {
+            base_vault_authority,
+            quote_vault_authority,
+            base_token_vault,
+            quote_token_vault,
+            base_vault_lp_address,
+            quote_vault_lp_address,
+            base_vault_lp_mint_address,
+            quote_vault_lp_mint_address,
+        } = get_accounts_from_state(&pool, &vault_a, &vault_b);
+        for (name, account, expected) in [
+            ("Pool LP mint", &self.pool_lp_mint, pool.lp_mint),
+            ("Vault A", &self.vault_a, base_vault_authority),
+            ("Vault B", &self.vault_b, quote_vault_authority),
+            (
+                "Vault A LP mint",
+                &self.vault_a_lp_mint,
+                base_vault_lp_mint_address,
+            ),
+            (
+                "Vault B LP mint",
+                &self.vault_b_lp_mint,
+                quote_vault_lp_mint_address,
+            ),
+            (
+                "Pool vault A LP token",
+                &self.pool_vault_a_lp_token,
+                base_vault_lp_address,
+            ),
+            (
+                "Pool vault B LP token",
+                &self.pool_vault_b_lp_token,
+                quote_vault_lp_address,
+            ),
+            ("Vault A token", &self.vault_a_token, base_token_vault),
+            ("Vault B token", &self.vault_b_token, quote_token_vault),
+        ] {
+            ensure!(
+                account.address == expected,
+                "{} {} does not match the state, expected {}",
+                name,
+                account.address,
+                expected
+            );
+        }
+
+        let vault_a_lp_mint = decode_mint(&self.vault_a_lp_mint.data)?;
+        let vault_b_lp_mint = decode_mint(&self.vault_b_lp_mint.data)?;
+
+        let pool_vault_a_lp_token = decode_token_account(&self.pool_vault_a_lp_token.data)?;
+        let pool_vault_b_lp_token = decode_token_account(&self.pool_vault_b_lp_token.data)?;
+
+        let vault_a_token = decode_token_account(&self.vault_a_token.data)?;
+        let vault_b_token = decode_token_account(&self.vault_b_token.data)?;
+
+        Ok(QuoteData {
+            pool,
+            curve_type,
+            vault_a,
+            vault_b,
+            pool_vault_a_lp_token,
+            pool_vault_b_lp_token,
+            vault_a_lp_mint_supply: vault_a_lp_mint.supply,
+            vault_b_lp_mint_supply: vault_b_lp_mint.supply,
+            vault_a_token,
+            vault_b_token,
+            clock,
+        })
+    }
+
+    /// Supply of the pool LP mint, read in the same batch as the pool reserves
+    pub fn pool_lp_supply(&self) -> anyhow::Result<u64> {
+        let pool = decode_pool(&self.pool.data)?;
+        ensure!(
+            self.pool_lp_mint.address == pool.lp_mint,
+            "Pool LP mint {} does not match the state, expected {}",
+            self.pool_lp_mint.address,
+            pool.lp_mint
+        );
+
+        Ok(decode_mint(&self.pool_lp_mint.data)?.supply)
+    }
+
+    /// Decode the snapshot into the pool state and pool LP supply an LP position is valued with.
+    /// Save the quote snapshot to compare the position against it later.
+    pub fn to_pool_lp_snapshot(&self) -> anyhow::Result<PoolLpSnapshot> {
+        Ok(PoolLpSnapshot {
+            quote_data: self.to_quote_data()?,
+            pool_lp_supply: self.pool_lp_supply()?,
+        })
+    }
+
+    /// Vault snapshots of vault A and vault B, read in the same batch as the pool
+    pub fn vault_snapshots(&self) -> [VaultSnapshot; 2] {
+        [
+            (&self.vault_a, &self.vault_a_lp_mint, &self.vault_a_token),
+            (&self.vault_b, &self.vault_b_lp_mint, &self.vault_b_token),
+        ]
+        .map(|(vault, lp_mint, token_vault)| VaultSnapshot {
+            version: VAULT_SNAPSHOT_VERSION,
+            slot: self.slot,
+            clock: self.clock.clone(),
+            vault: vault.clone(),
+            lp_mint: lp_mint.clone(),
+            token_vault: token_vault.clone(),
+        })
+    }
+
+    /// Export the snapshot as JSON
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        serde_json::to_string_pretty(self).context("Fail to serialize quote snapshot")
+    }
+
+    /// Import a snapshot from JSON, rejecting other versions of the format
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        let snapshot: Self =
+            serde_json::from_str(json).context("Fail to deserialize quote snapshot")?;
+        ensure!(
+            snapshot.version == QUOTE_SNAPSHOT_VERSION,
+            "Unsupported quote snapshot version {}, expected {}",
+            snapshot.version,
+            QUOTE_SNAPSHOT_VERSION
+        );
+
+        Ok(snapshot)
+    }
+
+    /// Write the snapshot to a JSON file
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_json()?)
+            .with_context(|| format!("Fail to write quote snapshot to {}", path.display()))
+    }
+
+    /// Read a snapshot from a JSON file
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Fail to read quote snapshot from {}", path.display()))?;
+
+        Self::from_json(&json)
+    }
+}
+
+/// Every account needed to quote deposits into and withdrawals from a vault, read in one batch
+/// at one slot. Snapshots taken over time give the realized yield of the vault.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VaultSnapshot {
+    /// Version of the snapshot format, see `VAULT_SNAPSHOT_VERSION`
+    pub version: u32,
+    /// Slot of the clock the accounts were read with
+    pub slot: u64,
+    pub clock: SnapshotAccount,
+    pub vault: SnapshotAccount,
+    pub lp_mint: SnapshotAccount,
+    pub token_vault: SnapshotAccount,
+}
+
+impl VaultSnapshot {
+    /// Decode the snapshot accounts into vault quote data. The LP mint and token vault must be
+    /// the ones the vault state points to.
+    pub fn to_vault_quote_data(&self) -> anyhow::Result<VaultQuoteData> {
+        ensure!(
+            self.clock.address == sysvar::clock::ID,
+            "Clock account is not the clock sysvar"
+        );
+        let clock = decode_clock(&self.clock.data)?;
+        ensure!(
+            clock.slot == self.slot,
+            "Snapshot slot does not match the clock"
+        );
+
+        let vault = decode_vault(&self.vault.data)?;
+        for (name, account, expected) in [
+            ("Vault LP mint", &self.lp_mint, vault.lp_mint),
+            ("Vault token", &self.token_vault, vault.token_vault),
+        ] {
+            ensure!(
+                account.address == expected,
+                "{} {} does not match the state, expected {}",
+                name,
+                account.address,
+                expected
+            );
+        }
+
+        let lp_mint = decode_mint(&self.lp_mint.data)?;
+        let token_vault = decode_token_account(&self.token_vault.data)?;
+
+        Ok(VaultQuoteData {
+            vault,
+            lp_mint_supply: lp_mint.supply,
+            token_vault,
+            clock,
+        })
+    }
+
+    /// Export the snapshot as JSON
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        serde_json::to_string_pretty(self).context("Fail to serialize vault snapshot")
+    }
+
+    /// Import a snapshot from JSON, rejecting other versions of the format
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        let snapshot: Self =
+            serde_json::from_str(json).context("Fail to deserialize vault snapshot")?;
+        ensure!(
+            snapshot.version == VAULT_SNAPSHOT_VERSION,
+            "Unsupported vault snapshot version {}, expected {}",
+            snapshot.version,
+            VAULT_SNAPSHOT_VERSION
+        );
+
+        Ok(snapshot)
+    }
+
+    /// Write the snapshot to a JSON file
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_json()?)
+            .with_context(|| format!("Fail to write vault snapshot to {}", path.display()))
+    }
+
+    /// Read a snapshot from a JSON file
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Fail to read vault snapshot from {}", path.display()))?;
+
+        Self::from_json(&json)
+    }
+}
+
+/// Swap executed on chain against the pool of `snapshot`, which holds the accounts right before
+/// the swap. Recorded swaps check the quote against the amounts the program paid out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedSwap {
+    /// Signature of the swap transaction
+    pub signature: String,
+    /// Mint of the token swapped in
+    #[serde(with = "pubkey_string")]
+    pub in_token_mint: Pubkey,
+    /// Amount the user paid
+    pub in_amount: u64,
+    /// Amount the user received
+    pub out_amount: u64,
+    /// Accounts at the slot before the swap
+    pub snapshot: QuoteSnapshot,
+}
+
+impl RecordedSwap {
+    /// Export the recorded swap as JSON
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        serde_json::to_string_pretty(self).context("Fail to serialize recorded swap")
+    }
+
+    /// Import a recorded swap from JSON, rejecting other versions of the snapshot format
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        let swap: Self = serde_json::from_str(json).context("Fail to deserialize recorded swap")?;
+        ensure!(
+            swap.snapshot.version == QUOTE_SNAPSHOT_VERSION,
+            "Unsupported quote snapshot version {}, expected {}",
+            swap.snapshot.version,
+            QUOTE_SNAPSHOT_VERSION
+        );
+
+        Ok(swap)
+    }
+
+    /// Write the recorded swap to a JSON file
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_json()?)
+            .with_context(|| format!("Fail to write recorded swap to {}", path.display()))
+    }
+
+    /// Read a recorded swap from a JSON file
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Fail to read recorded swap from {}", path.display()))?;
+
+        Self::from_json(&json)
+    }
+}
+
+pub(crate) mod pubkey_string {
+    use super::*;
+
+    pub fn serialize<S: serde::Serializer>(
+        pubkey: &Pubkey,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(pubkey)
+    }
+
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Pubkey, D::Error> {
+        let pubkey = <String as Deserialize>::deserialize(deserializer)?;
+        Pubkey::from_str(&pubkey).map_err(serde::de::Error::custom)
+    }
+}
+
+mod base64_bytes {
+    use super::*;
+
+    pub fn serialize<S: serde::Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&BASE64_STANDARD.encode(data))
+    }
+
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<u8>, D::Error> {
+        let data = <String as Deserialize>::deserialize(deserializer)?;
+        BASE64_STANDARD
+            .decode(data)
+            .map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,50 @@
+use crate::state::{BootstrappingConfig, PoolFees};
+use anchor_lang::prelude::*;
+use solana_program::pubkey::Pubkey;
+use std::fmt::Debug;
+
+/// Size of the config account, including the 8 bytes discriminator
+pub const CONFIG_ACCOUNT_SIZE: usize = 8 + 332;
+
+#[derive(Debug, AnchorDeserialize, AnchorSerialize, Clone, Copy)]
+/// Preset of fees and activation rules that permissionless pools can be created from
+pub struct Config {
+    /// Fees of the pools created from the config
+    pub pool_fees: PoolFees,
+    /// Duration between the pool creation and its activation point
+    pub activation_duration: u64,
+    /// Alpha vault config of the pools created from the config
+    pub vault_config_key: Pubkey,
+    // If pool_creator_authority == Pubkey::default() anyone can create a pool with the config,
+    // Otherwise only pool_creator_authority is able to create the pool
+    pub pool_creator_authority: Pubkey,
+    /// Activation type, 0 means by slot, 1 means by timestamp
+    pub activation_type: u8,
+    /// Partner fee numerator of the pools created from the config
+    pub partner_fee_numerator: u64,
+    pub _padding: [u8; 219],
+}
+
+impl Config {
+    /// `sha256("account:Config")[..8]`
+    pub const DISCRIMINATOR: [u8; 8] = [155, 12, 170, 224, 30, 250, 204, 130];
+
+    /// Account discriminator of the config account
+    pub fn discriminator() -> [u8; 8] {
+        Self::DISCRIMINATOR
+    }
+
+    /// Check whether anyone is allowed to create a pool with the config
+    pub fn is_public(&self) -> bool {
+        self.pool_creator_authority == Pubkey::default()
+    }
+
+    /// Get the bootstrapping config of a pool created from the config at `current_point`
+    pub fn to_bootstrapping_config(&self, current_point: u64) -> Option<BootstrappingConfig> {
+        Some(BootstrappingConfig {
+            activation_point: current_point.checked_add(self.activation_duration)?,
+            vault_config_key: self.vault_config_key,
+            activation_type: self.activation_type,
+        })
+    }
+}
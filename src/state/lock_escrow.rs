@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use solana_program::pubkey::Pubkey;
+use std::fmt::Debug;
+
+/// Offset of the owner in the lock escrow account, including the 8 bytes discriminator
+pub const LOCK_ESCROW_OWNER_OFFSET: usize = 8 + 32;
+
+#[derive(Debug, AnchorDeserialize, AnchorSerialize, Clone, Copy)]
+/// State of lock escrow account
+pub struct LockEscrow {
+    /// Pool address
+    pub pool: Pubkey,
+    /// Owner address
+    pub owner: Pubkey,
+    /// Vault address, store the lock user lp
+    pub escrow_vault: Pubkey,
+    /// bump, used to sign
+    pub bump: u8,
+    /// Total locked amount
+    pub total_locked_amount: u64,
+    /// Lp per token, virtual price of lp token
+    pub lp_per_token: u128,
+    /// Unclaimed fee pending
+    pub unclaimed_fee_pending: u64,
+    /// Total a fee claimed so far
+    pub a_fee: u64,
+    /// Total b fee claimed so far
+    pub b_fee: u64,
+}
+
+impl LockEscrow {
+    /// `sha256("account:LockEscrow")[..8]`
+    pub const DISCRIMINATOR: [u8; 8] = [190, 106, 121, 6, 200, 182, 21, 75];
+
+    /// Account discriminator of the lock escrow account
+    pub fn discriminator() -> [u8; 8] {
+        Self::DISCRIMINATOR
+    }
+}
@@ -1,7 +1,14 @@
+mod config;
+mod lock_escrow;
 mod pool;
 mod swap;
 mod vault;
 
+pub use config::*;
+pub use lock_escrow::*;
 pub use pool::*;
 pub use swap::*;
 pub use vault::*;
+
+#[cfg(test)]
+mod tests;
@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+pub use meteora_math::calculate_fee;
 use solana_program::pubkey::Pubkey;
 use std::fmt::Debug;
 
@@ -117,6 +118,16 @@ pub struct Pool {
     pub partner_info: PartnerInfo,
 }
 
+impl Pool {
+    /// `sha256("account:Pool")[..8]`
+    pub const DISCRIMINATOR: [u8; 8] = [241, 154, 109, 4, 17, 177, 109, 188];
+
+    /// Account discriminator of the pool account
+    pub fn discriminator() -> [u8; 8] {
+        Self::DISCRIMINATOR
+    }
+}
+
 // /// Padding for future pool field
 // pub padding: Padding,
 // /// The type of the swap curve supported by the pool.
@@ -136,6 +147,44 @@ pub struct Bootstrapping {
     pub activation_type: u8,
 }
 
+impl Bootstrapping {
+    /// Get the current point in the unit of the activation type
+    pub fn get_current_point(&self, clock: &Clock) -> std::result::Result<u64, String> {
+        match ActivationType::try_from(self.activation_type)? {
+            ActivationType::Slot => Ok(clock.slot),
+            ActivationType::Timestamp => Ok(clock.unix_timestamp as u64),
+        }
+    }
+
+    /// Get the slots or seconds left until the pool is activated, zero once activated
+    pub fn get_activation_countdown(
+        &self,
+        clock: &Clock,
+    ) -> std::result::Result<ActivationCountdown, String> {
+        let remaining = self
+            .activation_point
+            .saturating_sub(self.get_current_point(clock)?);
+        match ActivationType::try_from(self.activation_type)? {
+            ActivationType::Slot => Ok(ActivationCountdown::Slots(remaining)),
+            ActivationType::Timestamp => Ok(ActivationCountdown::Seconds(remaining)),
+        }
+    }
+
+    /// Check whether the vault is allowed to buy before the activation point
+    pub fn is_whitelisted_vault(&self, vault: &Pubkey) -> bool {
+        self.whitelisted_vault != Pubkey::default() && self.whitelisted_vault == *vault
+    }
+}
+
+/// Time left until the pool is activated
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ActivationCountdown {
+    /// Slots left, for pools activated by slot
+    Slots(u64),
+    /// Seconds left, for pools activated by timestamp
+    Seconds(u64),
+}
+
 #[derive(Copy, Clone, Debug, AnchorDeserialize, AnchorSerialize, Default)]
 pub struct PartnerInfo {
     pub fee_numerator: u64,        // 8
@@ -224,20 +273,16 @@ pub struct PoolFees {
     pub protocol_trade_fee_denominator: u64,
 }
 
-#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug, PartialEq)]
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug, PartialEq, Default)]
 pub enum PoolType {
     /// Permissioned
+    #[default]
     Permissioned,
     /// Permissionless
     Permissionless,
 }
 
-impl Default for PoolType {
-    fn default() -> Self {
-        PoolType::Permissioned
-    }
-}
-
+#[derive(Copy, Clone, Debug)]
 pub struct BootstrappingConfig {
     pub activation_point: u64,
     pub vault_config_key: Pubkey,
@@ -247,39 +292,22 @@ pub struct BootstrappingConfig {
 impl PoolFees {
     /// Calculate the trading fee in trading tokens
     pub fn trading_fee(&self, trading_tokens: u128) -> Option<u128> {
-        calculate_fee(
-            trading_tokens,
-            u128::try_from(self.trade_fee_numerator).ok()?,
-            u128::try_from(self.trade_fee_denominator).ok()?,
-        )
+        meteora_math::PoolFees::from(*self).trading_fee(trading_tokens)
     }
 
     /// Calculate the protocol trading fee in trading tokens
     pub fn protocol_trading_fee(&self, trading_tokens: u128) -> Option<u128> {
-        calculate_fee(
-            trading_tokens,
-            u128::try_from(self.protocol_trade_fee_numerator).ok()?,
-            u128::try_from(self.protocol_trade_fee_denominator).ok()?,
-        )
+        meteora_math::PoolFees::from(*self).protocol_trading_fee(trading_tokens)
     }
 }
 
-/// Helper function for calculating swap fee
-pub fn calculate_fee(
-    token_amount: u128,
-    fee_numerator: u128,
-    fee_denominator: u128,
-) -> Option<u128> {
-    if fee_numerator == 0 || token_amount == 0 {
-        Some(0)
-    } else {
-        let fee = token_amount
-            .checked_mul(fee_numerator)?
-            .checked_div(fee_denominator)?;
-        if fee == 0 {
-            Some(1) // minimum fee of one token
-        } else {
-            Some(fee)
+impl From<PoolFees> for meteora_math::PoolFees {
+    fn from(fees: PoolFees) -> Self {
+        Self {
+            trade_fee_numerator: fees.trade_fee_numerator,
+            trade_fee_denominator: fees.trade_fee_denominator,
+            protocol_trade_fee_numerator: fees.protocol_trade_fee_numerator,
+            protocol_trade_fee_denominator: fees.protocol_trade_fee_denominator,
         }
     }
 }
@@ -0,0 +1,340 @@
+use super::*;
+use crate::decoder::decode_config;
+use anchor_lang::AnchorSerialize;
+use proptest::prelude::{any, prop_assert, prop_oneof, proptest, Just, Strategy};
+use solana_program::clock::Clock;
+use solana_program::pubkey::Pubkey;
+
+fn vault(total_amount: u64, locked_profit_tracker: LockedProfitTracker) -> Vault {
+    Vault {
+        enabled: 1,
+        bumps: VaultBumps::default(),
+        total_amount,
+        token_vault: Pubkey::default(),
+        fee_vault: Pubkey::default(),
+        token_mint: Pubkey::default(),
+        lp_mint: Pubkey::default(),
+        strategies: [Pubkey::default(); MAX_STRATEGY],
+        base: Pubkey::default(),
+        admin: Pubkey::default(),
+        operator: Pubkey::default(),
+        locked_profit_tracker,
+    }
+}
+
+fn locked_profit_tracker() -> impl Strategy<Value = LockedProfitTracker> {
+    (any::<u64>(), any::<u64>(), any::<u64>()).prop_map(
+        |(last_updated_locked_profit, last_report, locked_profit_degradation)| {
+            LockedProfitTracker {
+                last_updated_locked_profit,
+                last_report,
+                locked_profit_degradation,
+            }
+        },
+    )
+}
+
+fn trade_direction() -> impl Strategy<Value = TradeDirection> {
+    prop_oneof![Just(TradeDirection::AtoB), Just(TradeDirection::BtoA)]
+}
+
+fn account_discriminator(name: &str) -> [u8; 8] {
+    let hash = solana_program::hash::hash(format!("account:{}", name).as_bytes());
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash.to_bytes()[..8]);
+    discriminator
+}
+
+#[test]
+fn discriminators_match_the_account_names() {
+    assert_eq!(Pool::discriminator(), account_discriminator("Pool"));
+    assert_eq!(Vault::discriminator(), account_discriminator("Vault"));
+    assert_eq!(Config::discriminator(), account_discriminator("Config"));
+    assert_eq!(
+        super::Strategy::discriminator(),
+        account_discriminator("Strategy")
+    );
+    assert_eq!(
+        LockEscrow::discriminator(),
+        account_discriminator("LockEscrow")
+    );
+}
+
+fn bootstrapping(activation_type: ActivationType, activation_point: u64) -> Bootstrapping {
+    Bootstrapping {
+        activation_point,
+        activation_type: activation_type as u8,
+        ..Bootstrapping::default()
+    }
+}
+
+fn clock(slot: u64, unix_timestamp: i64) -> Clock {
+    Clock {
+        slot,
+        unix_timestamp,
+        ..Clock::default()
+    }
+}
+
+#[test]
+fn activation_countdown_uses_the_activation_type() {
+    let clock = clock(1_000, 1_700_000_000);
+
+    assert_eq!(
+        bootstrapping(ActivationType::Slot, 1_250).get_activation_countdown(&clock),
+        Ok(ActivationCountdown::Slots(250))
+    );
+    assert_eq!(
+        bootstrapping(ActivationType::Timestamp, 1_700_000_060).get_activation_countdown(&clock),
+        Ok(ActivationCountdown::Seconds(60))
+    );
+}
+
+#[test]
+fn activation_countdown_is_zero_once_activated() {
+    let clock = clock(1_000, 1_700_000_000);
+
+    assert_eq!(
+        bootstrapping(ActivationType::Slot, 1_000).get_activation_countdown(&clock),
+        Ok(ActivationCountdown::Slots(0))
+    );
+    assert_eq!(
+        bootstrapping(ActivationType::Timestamp, 0).get_activation_countdown(&clock),
+        Ok(ActivationCountdown::Seconds(0))
+    );
+}
+
+#[test]
+fn activation_countdown_rejects_unknown_activation_type() {
+    let bootstrapping = Bootstrapping {
+        activation_type: 2,
+        ..Bootstrapping::default()
+    };
+
+    assert!(bootstrapping
+        .get_activation_countdown(&clock(1_000, 1_700_000_000))
+        .is_err());
+}
+
+#[test]
+fn only_the_whitelisted_vault_is_whitelisted() {
+    let whitelisted_vault = Pubkey::new_unique();
+    let bootstrapping = Bootstrapping {
+        whitelisted_vault,
+        ..Bootstrapping::default()
+    };
+
+    assert!(bootstrapping.is_whitelisted_vault(&whitelisted_vault));
+    assert!(!bootstrapping.is_whitelisted_vault(&Pubkey::new_unique()));
+}
+
+#[test]
+fn default_whitelisted_vault_whitelists_nobody() {
+    assert!(!Bootstrapping::default().is_whitelisted_vault(&Pubkey::default()));
+}
+
+fn config(activation_duration: u64) -> Config {
+    Config {
+        pool_fees: PoolFees {
+            trade_fee_numerator: 25,
+            trade_fee_denominator: 10_000,
+            protocol_trade_fee_numerator: 20,
+            protocol_trade_fee_denominator: 100,
+        },
+        activation_duration,
+        vault_config_key: Pubkey::new_unique(),
+        pool_creator_authority: Pubkey::default(),
+        activation_type: ActivationType::Timestamp as u8,
+        partner_fee_numerator: 5,
+        _padding: [0; 219],
+    }
+}
+
+#[test]
+fn config_layout_round_trip() {
+    let config = config(3_600);
+    let mut data = Config::discriminator().to_vec();
+    config.serialize(&mut data).unwrap();
+
+    assert_eq!(data.len(), CONFIG_ACCOUNT_SIZE);
+    // Fees, activation duration, vault config key and pool creator authority come first
+    assert_eq!(data[8 + 32 + 8 + 32 + 32], config.activation_type);
+    let decoded = decode_config(&data).unwrap();
+    assert_eq!(
+        meteora_math::PoolFees::from(decoded.pool_fees),
+        meteora_math::PoolFees::from(config.pool_fees)
+    );
+    assert_eq!(decoded.activation_duration, config.activation_duration);
+    assert_eq!(decoded.vault_config_key, config.vault_config_key);
+    assert!(decoded.is_public());
+    assert_eq!(decoded.activation_type, config.activation_type);
+    assert_eq!(decoded.partner_fee_numerator, config.partner_fee_numerator);
+    assert!(decode_config(&data[..CONFIG_ACCOUNT_SIZE - 1]).is_err());
+}
+
+#[test]
+fn config_pools_activate_after_the_activation_duration() {
+    let config = config(3_600);
+
+    let bootstrapping_config = config.to_bootstrapping_config(1_732_000_000).unwrap();
+
+    assert_eq!(bootstrapping_config.activation_point, 1_732_003_600);
+    assert_eq!(
+        bootstrapping_config.vault_config_key,
+        config.vault_config_key
+    );
+    assert_eq!(bootstrapping_config.activation_type, config.activation_type);
+    assert!(config.to_bootstrapping_config(u64::MAX).is_none());
+}
+
+proptest! {
+    #[test]
+    fn swap_never_decreases_k(
+        source_amount in any::<u64>(),
+        swap_source_amount in any::<u64>(),
+        swap_destination_amount in any::<u64>(),
+        trade_direction in trade_direction(),
+    ) {
+        let curve = ConstantProduct {};
+        if let Some(result) = curve.swap(
+            source_amount,
+            swap_source_amount,
+            swap_destination_amount,
+            trade_direction,
+        ) {
+            let k = u128::from(swap_source_amount) * u128::from(swap_destination_amount);
+            let new_source = u128::from(swap_source_amount) + u128::from(source_amount);
+            let new_destination =
+                u128::from(swap_destination_amount) - result.destination_amount_swapped;
+            // The product of two u64 fits in a u128, and so do the post swap reserves, which
+            // are at most twice as large as a u64 for the source and a u64 for the destination
+            prop_assert!(new_source.checked_mul(new_destination).is_none_or(|new_k| new_k >= k));
+            prop_assert!(result.new_swap_source_amount * result.new_swap_destination_amount >= k);
+        }
+    }
+
+    #[test]
+    fn swap_output_never_exceeds_reserve(
+        source_amount in any::<u64>(),
+        // An empty source reserve gives the whole destination reserve, like spl-math
+        swap_source_amount in 1..=u64::MAX,
+        swap_destination_amount in any::<u64>(),
+        trade_direction in trade_direction(),
+    ) {
+        let curve = ConstantProduct {};
+        if let Some(result) = curve.swap(
+            source_amount,
+            swap_source_amount,
+            swap_destination_amount,
+            trade_direction,
+        ) {
+            prop_assert!(result.destination_amount_swapped < u128::from(swap_destination_amount));
+            prop_assert!(result.source_amount_swapped <= u128::from(source_amount));
+        }
+    }
+
+    #[test]
+    fn swap_round_trip_never_profits(
+        source_amount in any::<u64>(),
+        swap_source_amount in 1..=u64::MAX,
+        swap_destination_amount in 1..=u64::MAX,
+    ) {
+        let curve = ConstantProduct {};
+        let Some(forward) = curve.swap(
+            source_amount,
+            swap_source_amount,
+            swap_destination_amount,
+            TradeDirection::AtoB,
+        ) else {
+            return Ok(());
+        };
+        let (Ok(back_amount), Ok(new_source), Ok(new_destination)) = (
+            u64::try_from(forward.destination_amount_swapped),
+            u64::try_from(u128::from(swap_source_amount) + u128::from(source_amount)),
+            u64::try_from(u128::from(swap_destination_amount) - forward.destination_amount_swapped),
+        ) else {
+            return Ok(());
+        };
+
+        if let Some(backward) =
+            curve.swap(back_amount, new_destination, new_source, TradeDirection::BtoA)
+        {
+            prop_assert!(backward.destination_amount_swapped <= u128::from(source_amount));
+        }
+    }
+
+    #[test]
+    fn fee_never_exceeds_amount(
+        token_amount in any::<u64>(),
+        fee_denominator in 1..=u64::MAX,
+        fee_numerator in any::<u64>(),
+    ) {
+        let fee_numerator = fee_numerator % (fee_denominator + 1);
+        let fee = calculate_fee(
+            token_amount.into(),
+            fee_numerator.into(),
+            fee_denominator.into(),
+        )
+        .unwrap();
+
+        prop_assert!(fee <= u128::from(token_amount).max(1));
+        if fee_numerator != 0 && token_amount != 0 {
+            prop_assert!(fee >= 1);
+        }
+    }
+
+    #[test]
+    fn fee_never_panics(
+        token_amount in any::<u128>(),
+        fee_numerator in any::<u128>(),
+        fee_denominator in any::<u128>(),
+    ) {
+        calculate_fee(token_amount, fee_numerator, fee_denominator);
+    }
+
+    #[test]
+    fn locked_profit_never_increases(
+        tracker in locked_profit_tracker(),
+        current_time in any::<u64>(),
+        elapsed in any::<u64>(),
+    ) {
+        let later_time = current_time.saturating_add(elapsed);
+        if let (Some(locked_profit), Some(later_locked_profit)) = (
+            tracker.calculate_locked_profit(current_time),
+            tracker.calculate_locked_profit(later_time),
+        ) {
+            prop_assert!(later_locked_profit <= locked_profit);
+            prop_assert!(locked_profit <= tracker.last_updated_locked_profit);
+        }
+    }
+
+    #[test]
+    fn vault_share_round_trip_never_profits(
+        total_amount in any::<u64>(),
+        tracker in locked_profit_tracker(),
+        current_time in any::<u64>(),
+        out_token in any::<u64>(),
+        total_supply in any::<u64>(),
+    ) {
+        let vault = vault(total_amount, tracker);
+        if let Some(share) = vault.get_unmint_amount(current_time, out_token, total_supply) {
+            if let Some(amount) = vault.get_amount_by_share(current_time, share, total_supply) {
+                prop_assert!(amount <= out_token);
+            }
+        }
+    }
+
+    #[test]
+    fn vault_math_never_panics(
+        total_amount in any::<u64>(),
+        tracker in locked_profit_tracker(),
+        current_time in any::<u64>(),
+        amount in any::<u64>(),
+        total_supply in any::<u64>(),
+    ) {
+        let vault = vault(total_amount, tracker);
+        vault.get_unlocked_amount(current_time);
+        vault.get_amount_by_share(current_time, amount, total_supply);
+        vault.get_unmint_amount(current_time, amount, total_supply);
+    }
+}
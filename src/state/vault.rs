@@ -4,8 +4,9 @@ use std::fmt::Debug;
 
 /// Max strategy number that a vault can support
 pub const MAX_STRATEGY: usize = 30;
-/// DENOMINATOR of degradation
-pub const LOCKED_PROFIT_DEGRADATION_DENOMINATOR: u128 = 1_000_000_000_000;
+/// Max bump numer that a strategy can support
+pub const MAX_BUMPS: usize = 10;
+pub use meteora_math::LOCKED_PROFIT_DEGRADATION_DENOMINATOR;
 
 /// Vault struct
 #[derive(Debug, AnchorDeserialize, AnchorSerialize, Clone, Copy)]
@@ -37,6 +38,14 @@ pub struct Vault {
 }
 
 impl Vault {
+    /// `sha256("account:Vault")[..8]`
+    pub const DISCRIMINATOR: [u8; 8] = [211, 8, 232, 43, 2, 152, 117, 119];
+
+    /// Account discriminator of the vault account
+    pub fn discriminator() -> [u8; 8] {
+        Self::DISCRIMINATOR
+    }
+
     /// Get amount by share
     pub fn get_amount_by_share(
         &self,
@@ -44,20 +53,11 @@ impl Vault {
         share: u64,
         total_supply: u64,
     ) -> Option<u64> {
-        let total_amount = self.get_unlocked_amount(current_time)?;
-        u64::try_from(
-            u128::from(share)
-                .checked_mul(u128::from(total_amount))?
-                .checked_div(u128::from(total_supply))?,
-        )
-        .ok()
+        meteora_math::Vault::from(self).get_amount_by_share(current_time, share, total_supply)
     }
     /// Get unlocked amount of vault
     pub fn get_unlocked_amount(&self, current_time: u64) -> Option<u64> {
-        self.total_amount.checked_sub(
-            self.locked_profit_tracker
-                .calculate_locked_profit(current_time)?,
-        )
+        meteora_math::Vault::from(self).get_unlocked_amount(current_time)
     }
 
     /// Get unmint amount by token amount
@@ -67,13 +67,16 @@ impl Vault {
         out_token: u64,
         total_supply: u64,
     ) -> Option<u64> {
-        let total_amount = self.get_unlocked_amount(current_time)?;
-        u64::try_from(
-            u128::from(out_token)
-                .checked_mul(u128::from(total_supply))?
-                .checked_div(u128::from(total_amount))?,
-        )
-        .ok()
+        meteora_math::Vault::from(self).get_unmint_amount(current_time, out_token, total_supply)
+    }
+}
+
+impl From<&Vault> for meteora_math::Vault {
+    fn from(vault: &Vault) -> Self {
+        Self {
+            total_amount: vault.total_amount,
+            locked_profit_tracker: vault.locked_profit_tracker.into(),
+        }
     }
 }
 
@@ -100,19 +103,76 @@ pub struct LockedProfitTracker {
 impl LockedProfitTracker {
     /// Calculate locked profit, based from Yearn `https://github.com/yearn/yearn-vaults/blob/main/contracts/Vault.vy#L825`
     pub fn calculate_locked_profit(&self, current_time: u64) -> Option<u64> {
-        let duration = u128::from(current_time.checked_sub(self.last_report)?);
-        let locked_profit_degradation = u128::from(self.locked_profit_degradation);
-        let locked_fund_ratio = duration.checked_mul(locked_profit_degradation)?;
+        meteora_math::LockedProfitTracker::from(*self).calculate_locked_profit(current_time)
+    }
+}
 
-        if locked_fund_ratio > LOCKED_PROFIT_DEGRADATION_DENOMINATOR {
-            return Some(0);
+impl From<LockedProfitTracker> for meteora_math::LockedProfitTracker {
+    fn from(tracker: LockedProfitTracker) -> Self {
+        Self {
+            last_updated_locked_profit: tracker.last_updated_locked_profit,
+            last_report: tracker.last_report,
+            locked_profit_degradation: tracker.locked_profit_degradation,
         }
-        let locked_profit = u128::from(self.last_updated_locked_profit);
+    }
+}
+
+/// Strategy struct
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug)]
+pub struct Strategy {
+    /// Lending pool address, that the strategy will deposit/withdraw balance
+    pub reserve: Pubkey,
+    /// The token account, that holds the collateral token
+    pub collateral_vault: Pubkey,
+    /// Specify type of strategy
+    pub strategy_type: StrategyType,
+    /// The liquidity in strategy at the time vault deposit/withdraw from a lending protocol
+    pub current_liquidity: u64,
+    /// Hold some bumps, in case the strategy needs to use other seeds to sign a CPI call.
+    pub bumps: [u8; MAX_BUMPS],
+    /// Vault address, that the strategy belongs
+    pub vault: Pubkey,
+    /// If we remove strategy by remove_strategy2 endpoint, this account will be never added again
+    pub is_disable: u8,
+}
+
+impl Strategy {
+    /// `sha256("account:Strategy")[..8]`
+    pub const DISCRIMINATOR: [u8; 8] = [174, 110, 39, 119, 82, 106, 169, 102];
 
-        let locked_profit = (locked_profit
-            .checked_mul(LOCKED_PROFIT_DEGRADATION_DENOMINATOR - locked_fund_ratio)?)
-        .checked_div(LOCKED_PROFIT_DEGRADATION_DENOMINATOR)?;
-        let locked_profit = u64::try_from(locked_profit).ok()?;
-        Some(locked_profit)
+    /// Account discriminator of the strategy account
+    pub fn discriminator() -> [u8; 8] {
+        Self::DISCRIMINATOR
     }
 }
+
+/// StrategyType struct
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug, PartialEq)]
+pub enum StrategyType {
+    /// Deposit in PortFinance’s reserve to get collateral, the value of collateral will increase overtime by accruing interest, and we can claim more liquidity later
+    PortFinanceWithoutLM,
+    /// Currently we don’t support this strategy
+    PortFinanceWithLM,
+    /// Deposit in Solend’s reserve
+    SolendWithoutLM,
+    /// Deposit in Mango’s reserve
+    Mango,
+    /// Deposit in Solend’s reserve with obligation
+    SolendWithLM,
+    /// Deposit in Apricot’s reserve
+    ApricotWithoutLM,
+    /// Deposit in Francium’s reserve
+    Francium,
+    /// Deposit in Tulip's reserve
+    Tulip,
+    /// This implementation is to compatible with remove_strategy2 endpoint
+    Vault,
+    /// Deposit in Drift's spot market
+    Drift,
+    /// Deposit in Frakt
+    Frakt,
+    /// Deposit in Marginfi
+    Marginfi,
+    /// Deposit in Kamino
+    Kamino,
+}
@@ -0,0 +1,399 @@
+//! Builders of synthetic quote snapshots for tests. Accounts are encoded with the on-chain
+//! layouts, so the snapshots decode through the same path as the ones fetched from RPC.
+//!
+//! The state is made up: balances only have the magnitudes of mainnet pools, and every address
+//! is a synthetic key, so no fixture account can be mistaken for a mainnet one. Amounts the
+//! tests expect are regression values of this quoter, not amounts of recorded swaps.
+
+use crate::constant::{
+    METEORA_DYN_PROGRAM_ID, METEORA_DYN_VAULT_BASE_ID, METEORA_DYN_VAULT_PROGRAM_ID,
+};
+use crate::quote_service::{get_all_accounts_for_quote, MeteoraAccounts};
+use crate::snapshot::{QuoteSnapshot, SnapshotAccount, QUOTE_SNAPSHOT_VERSION};
+use crate::state::*;
+use anchor_lang::AnchorSerialize;
+use anchor_spl::token::spl_token;
+use anchor_spl::token::spl_token::solana_program::program_option::COption;
+use anchor_spl::token::spl_token::solana_program::program_pack::Pack;
+use solana_program::clock::Clock;
+use solana_program::pubkey::Pubkey;
+use solana_program::sysvar;
+
+/// Offset of the curve type in the pool account
+const CURVE_TYPE_OFFSET: usize = 874;
+
+pub const METAV_MINT: Pubkey = synthetic_address(1);
+pub const USDC_MINT: Pubkey = synthetic_address(2);
+pub const WSOL_MINT: Pubkey = synthetic_address(3);
+pub const METAV_WSOL_POOL: Pubkey = synthetic_address(4);
+pub const USDC_WSOL_POOL: Pubkey = synthetic_address(5);
+pub const LEGACY_VAULT_TOKEN_MINT: Pubkey = synthetic_address(6);
+/// LP mint of the legacy vault, not the `lp_mint` PDA like the LP mints of the first vaults
+pub const LEGACY_VAULT_LP_MINT: Pubkey = synthetic_address(7);
+pub const LEGACY_VAULT_POOL: Pubkey = synthetic_address(8);
+
+pub const SLOT: u64 = 305_112_448;
+pub const UNIX_TIMESTAMP: i64 = 1_732_000_000;
+/// Supply of the pool LP mint of every pool fixture
+pub const POOL_LP_SUPPLY: u64 = 3_162_277_660_168;
+/// Locked profit fully released in 6 hours
+pub const LOCKED_PROFIT_DEGRADATION: u64 = 46_296_296;
+
+/// Address made of the same byte, unlike any real account
+const fn synthetic_address(seed: u8) -> Pubkey {
+    Pubkey::new_from_array([seed; 32])
+}
+
+/// State of one vault of the pool
+#[derive(Debug, Clone, Copy)]
+pub struct VaultFixture {
+    pub token_mint: Pubkey,
+    /// LP mint of legacy vaults, `None` for the `lp_mint` PDA
+    pub legacy_lp_mint: Option<Pubkey>,
+    pub total_amount: u64,
+    /// Amount held by the vault reserve, the rest of the total amount is in strategies
+    pub token_vault_amount: u64,
+    pub lp_mint_supply: u64,
+    /// Vault LP held by the pool
+    pub pool_lp_amount: u64,
+    pub locked_profit_tracker: LockedProfitTracker,
+}
+
+/// State of a constant product pool and its vaults
+#[derive(Debug, Clone, Copy)]
+pub struct PoolFixture {
+    pub pool_address: Pubkey,
+    pub vault_a: VaultFixture,
+    pub vault_b: VaultFixture,
+    pub fees: PoolFees,
+    pub pool_lp_supply: u64,
+    pub activation_point: u64,
+    pub activation_type: u8,
+    pub slot: u64,
+    pub unix_timestamp: i64,
+}
+
+impl PoolFixture {
+    /// Encode the pool, vaults, mints, token accounts and clock into a snapshot
+    pub fn snapshot(&self) -> QuoteSnapshot {
+        let dynamic_amm_program = METEORA_DYN_PROGRAM_ID;
+        let vault_program = METEORA_DYN_VAULT_PROGRAM_ID;
+
+        let MeteoraAccounts {
+            base_vault_authority,
+            quote_vault_authority,
+            base_token_vault,
+            quote_token_vault,
+            base_vault_lp_address,
+            quote_vault_lp_address,
+            base_vault_lp_mint_address,
+            quote_vault_lp_mint_address,
+        } = get_all_accounts_for_quote(
+            self.vault_a.token_mint,
+            self.vault_b.token_mint,
+            self.pool_address,
+        );
+        let vault_a_lp_mint = self
+            .vault_a
+            .legacy_lp_mint
+            .unwrap_or(base_vault_lp_mint_address);
+        let vault_b_lp_mint = self
+            .vault_b
+            .legacy_lp_mint
+            .unwrap_or(quote_vault_lp_mint_address);
+
+        let pool_lp_mint = Pubkey::find_program_address(
+            &[b"lp_mint", self.pool_address.as_ref()],
+            &dynamic_amm_program,
+        )
+        .0;
+        let pool = Pool {
+            lp_mint: pool_lp_mint,
+            token_a_mint: self.vault_a.token_mint,
+            token_b_mint: self.vault_b.token_mint,
+            a_vault: base_vault_authority,
+            b_vault: quote_vault_authority,
+            a_vault_lp: base_vault_lp_address,
+            b_vault_lp: quote_vault_lp_address,
+            a_vault_lp_bump: 0,
+            enabled: true,
+            protocol_token_a_fee: Pubkey::default(),
+            protocol_token_b_fee: Pubkey::default(),
+            fee_last_updated_at: 0,
+            _padding0: [0; 24],
+            fees: self.fees,
+            pool_type: PoolType::Permissionless,
+            stake: Pubkey::default(),
+            total_locked_lp: 0,
+            bootstrapping: Bootstrapping {
+                activation_point: self.activation_point,
+                activation_type: self.activation_type,
+                ..Default::default()
+            },
+            partner_info: PartnerInfo::default(),
+        };
+        let mut pool_data = Pool::discriminator().to_vec();
+        pool.serialize(&mut pool_data).unwrap();
+        pool_data.resize(CURVE_TYPE_OFFSET, 0);
+        CurveType::ConstantProduct
+            .serialize(&mut pool_data)
+            .unwrap();
+
+        let clock = Clock {
+            slot: self.slot,
+            unix_timestamp: self.unix_timestamp,
+            ..Clock::default()
+        };
+
+        let account = |address: Pubkey, owner: Pubkey, data: Vec<u8>| SnapshotAccount {
+            address,
+            owner,
+            lamports: 1_000_000_000,
+            data,
+        };
+        let vault_account = |address: Pubkey, fixture: &VaultFixture, token_vault, lp_mint| {
+            account(
+                address,
+                vault_program,
+                encode_vault(fixture, token_vault, lp_mint),
+            )
+        };
+        let mint_account = |address: Pubkey, authority: Pubkey, supply: u64| {
+            account(address, spl_token::ID, encode_mint(authority, supply))
+        };
+        let token_account = |address: Pubkey, mint: Pubkey, owner: Pubkey, amount: u64| {
+            account(
+                address,
+                spl_token::ID,
+                encode_token_account(mint, owner, amount),
+            )
+        };
+
+        QuoteSnapshot {
+            version: QUOTE_SNAPSHOT_VERSION,
+            slot: self.slot,
+            clock: account(
+                sysvar::clock::ID,
+                sysvar::ID,
+                bincode::serialize(&clock).unwrap(),
+            ),
+            pool: account(self.pool_address, dynamic_amm_program, pool_data),
+            pool_lp_mint: mint_account(pool_lp_mint, self.pool_address, self.pool_lp_supply),
+            vault_a: vault_account(
+                base_vault_authority,
+                &self.vault_a,
+                base_token_vault,
+                vault_a_lp_mint,
+            ),
+            vault_b: vault_account(
+                quote_vault_authority,
+                &self.vault_b,
+                quote_token_vault,
+                vault_b_lp_mint,
+            ),
+            vault_a_lp_mint: mint_account(
+                vault_a_lp_mint,
+                base_vault_authority,
+                self.vault_a.lp_mint_supply,
+            ),
+            vault_b_lp_mint: mint_account(
+                vault_b_lp_mint,
+                quote_vault_authority,
+                self.vault_b.lp_mint_supply,
+            ),
+            pool_vault_a_lp_token: token_account(
+                base_vault_lp_address,
+                vault_a_lp_mint,
+                self.pool_address,
+                self.vault_a.pool_lp_amount,
+            ),
+            pool_vault_b_lp_token: token_account(
+                quote_vault_lp_address,
+                vault_b_lp_mint,
+                self.pool_address,
+                self.vault_b.pool_lp_amount,
+            ),
+            vault_a_token: token_account(
+                base_token_vault,
+                self.vault_a.token_mint,
+                base_vault_authority,
+                self.vault_a.token_vault_amount,
+            ),
+            vault_b_token: token_account(
+                quote_token_vault,
+                self.vault_b.token_mint,
+                quote_vault_authority,
+                self.vault_b.token_vault_amount,
+            ),
+        }
+    }
+}
+
+fn encode_vault(fixture: &VaultFixture, token_vault: Pubkey, lp_mint: Pubkey) -> Vec<u8> {
+    let vault = Vault {
+        enabled: 1,
+        bumps: VaultBumps::default(),
+        total_amount: fixture.total_amount,
+        token_vault,
+        fee_vault: Pubkey::default(),
+        token_mint: fixture.token_mint,
+        lp_mint,
+        strategies: [Pubkey::default(); MAX_STRATEGY],
+        base: METEORA_DYN_VAULT_BASE_ID,
+        admin: Pubkey::default(),
+        operator: Pubkey::default(),
+        locked_profit_tracker: fixture.locked_profit_tracker,
+    };
+    let mut data = Vault::discriminator().to_vec();
+    vault.serialize(&mut data).unwrap();
+    data
+}
+
+fn encode_mint(mint_authority: Pubkey, supply: u64) -> Vec<u8> {
+    let mut data = vec![0; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        mint_authority: COption::Some(mint_authority),
+        supply,
+        decimals: 9,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    }
+    .pack_into_slice(&mut data);
+    data
+}
+
+fn encode_token_account(mint: Pubkey, owner: Pubkey, amount: u64) -> Vec<u8> {
+    let mut data = vec![0; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint,
+        owner,
+        amount,
+        state: spl_token::state::AccountState::Initialized,
+        ..Default::default()
+    }
+    .pack_into_slice(&mut data);
+    data
+}
+
+/// Locked profit reported `seconds_ago` before the fixture clock
+pub fn locked_profit(amount: u64, seconds_ago: u64) -> LockedProfitTracker {
+    LockedProfitTracker {
+        last_updated_locked_profit: amount,
+        last_report: UNIX_TIMESTAMP as u64 - seconds_ago,
+        locked_profit_degradation: LOCKED_PROFIT_DEGRADATION,
+    }
+}
+
+/// WSOL vault, shared by the WSOL pools. Most of its liquidity is lent out to strategies, and
+/// part of its last profit is still locked.
+pub fn wsol_vault(pool_lp_amount: u64) -> VaultFixture {
+    VaultFixture {
+        token_mint: WSOL_MINT,
+        legacy_lp_mint: None,
+        total_amount: 1_284_559_301_882_455,
+        token_vault_amount: 402_117_559_003_118,
+        lp_mint_supply: 1_197_634_255_910_833,
+        pool_lp_amount,
+        locked_profit_tracker: locked_profit(1_482_337_118, 600),
+    }
+}
+
+/// USDC vault, with part of its last profit still locked
+pub fn usdc_vault(pool_lp_amount: u64) -> VaultFixture {
+    VaultFixture {
+        token_mint: USDC_MINT,
+        legacy_lp_mint: None,
+        total_amount: 9_836_420_118_733,
+        token_vault_amount: 2_950_926_035_619,
+        lp_mint_supply: 9_129_004_552_780,
+        pool_lp_amount,
+        locked_profit_tracker: locked_profit(88_214_553, 3_600),
+    }
+}
+
+fn pool_fixture(pool_address: Pubkey, vault_a: VaultFixture, vault_b: VaultFixture) -> PoolFixture {
+    PoolFixture {
+        pool_address,
+        vault_a,
+        vault_b,
+        fees: PoolFees {
+            trade_fee_numerator: 25,
+            trade_fee_denominator: 10_000,
+            protocol_trade_fee_numerator: 20,
+            protocol_trade_fee_denominator: 100,
+        },
+        pool_lp_supply: POOL_LP_SUPPLY,
+        activation_point: 0,
+        activation_type: ActivationType::Slot as u8,
+        slot: SLOT,
+        unix_timestamp: UNIX_TIMESTAMP,
+    }
+}
+
+/// METAV/WSOL pool. The METAV vault has no strategy and no locked profit, and the pool holds all
+/// of its LP.
+pub fn metav_wsol_pool() -> PoolFixture {
+    let metav_vault = VaultFixture {
+        token_mint: METAV_MINT,
+        legacy_lp_mint: None,
+        total_amount: 48_213_775_412_903_551,
+        token_vault_amount: 48_213_775_412_903_551,
+        lp_mint_supply: 47_950_112_006_221_870,
+        pool_lp_amount: 47_950_112_006_221_870,
+        locked_profit_tracker: locked_profit(0, UNIX_TIMESTAMP as u64),
+    };
+
+    pool_fixture(METAV_WSOL_POOL, metav_vault, wsol_vault(37_813_001_337_202))
+}
+
+/// Pool of two arbitrary tokens. Both vaults hold the balances of the USDC vault, so the price of
+/// the pool is the ratio of its vault LP amounts.
+pub fn token_pool(
+    pool_address: Pubkey,
+    (token_a_mint, pool_a_lp_amount): (Pubkey, u64),
+    (token_b_mint, pool_b_lp_amount): (Pubkey, u64),
+) -> PoolFixture {
+    pool_fixture(
+        pool_address,
+        VaultFixture {
+            token_mint: token_a_mint,
+            ..usdc_vault(pool_a_lp_amount)
+        },
+        VaultFixture {
+            token_mint: token_b_mint,
+            ..usdc_vault(pool_b_lp_amount)
+        },
+    )
+}
+
+/// USDC/WSOL pool, both vaults with locked profit
+pub fn usdc_wsol_pool() -> PoolFixture {
+    pool_fixture(
+        USDC_WSOL_POOL,
+        usdc_vault(1_522_664_910_001),
+        wsol_vault(7_102_443_118_004),
+    )
+}
+
+/// Pool of a legacy vault token against USDC
+pub fn legacy_vault_pool() -> PoolFixture {
+    let legacy_vault = VaultFixture {
+        token_mint: LEGACY_VAULT_TOKEN_MINT,
+        legacy_lp_mint: Some(LEGACY_VAULT_LP_MINT),
+        total_amount: 250_331_902_118,
+        token_vault_amount: 250_331_902_118,
+        lp_mint_supply: 249_887_001_556,
+        pool_lp_amount: 120_556_773_004,
+        locked_profit_tracker: locked_profit(0, UNIX_TIMESTAMP as u64),
+    };
+
+    PoolFixture {
+        fees: PoolFees {
+            trade_fee_numerator: 5,
+            trade_fee_denominator: 10_000,
+            protocol_trade_fee_numerator: 20,
+            protocol_trade_fee_denominator: 100,
+        },
+        ..pool_fixture(LEGACY_VAULT_POOL, legacy_vault, usdc_vault(118_004_227_351))
+    }
+}
@@ -0,0 +1,159 @@
+use crate::snapshot::VaultSnapshot;
+use crate::state::LOCKED_PROFIT_DEGRADATION_DENOMINATOR;
+use crate::vault_quote::VaultQuoteData;
+use anyhow::{ensure, Context};
+use std::convert::TryInto;
+
+/// Seconds in a 365 days year
+pub const SECONDS_PER_YEAR: f64 = 365.0 * 24.0 * 60.0 * 60.0;
+
+/// LP virtual price of the vault at a point in time
+#[derive(Debug, Clone, Copy)]
+pub struct VirtualPricePoint {
+    /// Unix timestamp of the snapshot
+    pub unix_timestamp: i64,
+    /// Unlocked amount of the vault per LP
+    pub virtual_price: f64,
+}
+
+/// Profit of the last strategy report that is still being released
+#[derive(Debug, Clone, Copy)]
+pub struct LockedProfitRelease {
+    /// Profit still locked at the time of the snapshot
+    pub locked_profit: u64,
+    /// Unix timestamp at which all the profit is released, `None` when the vault has no
+    /// degradation and the profit is never released
+    pub fully_released_at: Option<u64>,
+    /// Virtual price once all the profit is released, at the LP supply of the snapshot
+    pub virtual_price_after_release: f64,
+}
+
+/// Realized yield of a vault over its snapshots
+#[derive(Debug, Clone)]
+pub struct VaultYield {
+    /// Virtual price of every snapshot, oldest first
+    pub points: Vec<VirtualPricePoint>,
+    /// Annualized, compounded growth of the virtual price between the first and last snapshot
+    pub apy: f64,
+    /// Locked profit of the latest snapshot
+    pub locked_profit_release: LockedProfitRelease,
+}
+
+/// Get the LP virtual price of the vault, the unlocked amount per LP
+pub fn get_virtual_price(vault_quote_data: &VaultQuoteData) -> anyhow::Result<f64> {
+    let current_time: u64 = vault_quote_data.clock.unix_timestamp.try_into()?;
+    ensure!(
+        vault_quote_data.lp_mint_supply > 0,
+        "Vault has no LP supply"
+    );
+
+    let unlocked_amount = vault_quote_data
+        .vault
+        .get_unlocked_amount(current_time)
+        .context("Fail to get unlocked amount")?;
+
+    Ok(unlocked_amount as f64 / vault_quote_data.lp_mint_supply as f64)
+}
+
+/// Get the profit still being released by the degradation schedule of the vault
+pub fn get_locked_profit_release(
+    vault_quote_data: &VaultQuoteData,
+) -> anyhow::Result<LockedProfitRelease> {
+    let current_time: u64 = vault_quote_data.clock.unix_timestamp.try_into()?;
+    let tracker = &vault_quote_data.vault.locked_profit_tracker;
+    ensure!(
+        vault_quote_data.lp_mint_supply > 0,
+        "Vault has no LP supply"
+    );
+
+    let locked_profit = tracker
+        .calculate_locked_profit(current_time)
+        .context("Fail to calculate locked profit")?;
+
+    // The locked fund ratio reaches the denominator after denominator / degradation seconds.
+    // Without degradation the profit stays locked forever.
+    let fully_released_at = match tracker.locked_profit_degradation {
+        0 if locked_profit > 0 => None,
+        0 => Some(tracker.last_report),
+        locked_profit_degradation => {
+            let release_duration = u64::try_from(
+                LOCKED_PROFIT_DEGRADATION_DENOMINATOR
+                    .div_ceil(u128::from(locked_profit_degradation)),
+            )?;
+            Some(
+                tracker
+                    .last_report
+                    .checked_add(release_duration)
+                    .context("Fail to get fully released time")?,
+            )
+        }
+    };
+
+    Ok(LockedProfitRelease {
+        locked_profit,
+        fully_released_at,
+        virtual_price_after_release: vault_quote_data.vault.total_amount as f64
+            / vault_quote_data.lp_mint_supply as f64,
+    })
+}
+
+/// Compute the realized APY of the vault from snapshots taken over time
+pub fn compute_vault_yield(snapshots: &[VaultQuoteData]) -> anyhow::Result<VaultYield> {
+    let mut snapshots: Vec<&VaultQuoteData> = snapshots.iter().collect();
+    snapshots.sort_by_key(|snapshot| snapshot.clock.unix_timestamp);
+
+    let points = snapshots
+        .iter()
+        .map(|snapshot| {
+            Ok(VirtualPricePoint {
+                unix_timestamp: snapshot.clock.unix_timestamp,
+                virtual_price: get_virtual_price(snapshot)?,
+            })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let (first, last) = match (points.first(), points.last()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => anyhow::bail!("No snapshot"),
+    };
+    ensure!(
+        last.unix_timestamp > first.unix_timestamp,
+        "Snapshots must span some time"
+    );
+    ensure!(first.virtual_price > 0.0, "First virtual price is zero");
+
+    let duration = (last.unix_timestamp - first.unix_timestamp) as f64;
+    let apy = (last.virtual_price / first.virtual_price).powf(SECONDS_PER_YEAR / duration) - 1.0;
+
+    let latest = snapshots.last().context("No snapshot")?;
+
+    Ok(VaultYield {
+        points,
+        apy,
+        locked_profit_release: get_locked_profit_release(latest)?,
+    })
+}
+
+/// Compute the realized APY of the vault from snapshots stored over time, e.g. with
+/// `QuoteService::save_vault_snapshot`. The snapshots must all be of the same vault.
+pub fn compute_vault_yield_from_snapshots(
+    snapshots: &[VaultSnapshot],
+) -> anyhow::Result<VaultYield> {
+    let vault_address = snapshots.first().context("No snapshot")?.vault.address;
+    ensure!(
+        snapshots
+            .iter()
+            .all(|snapshot| snapshot.vault.address == vault_address),
+        "Snapshots are not of the same vault"
+    );
+
+    let vault_quote_data = snapshots
+        .iter()
+        .map(VaultSnapshot::to_vault_quote_data)
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    compute_vault_yield(&vault_quote_data)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,197 @@
+use super::*;
+use crate::state::LockedProfitTracker;
+use crate::test_fixtures::*;
+
+/// Pool LP amount of the USDC vault fixture, any amount gives the same vault
+const POOL_LP_AMOUNT: u64 = 1_522_664_910_001;
+
+fn usdc_vault_snapshot(
+    unix_timestamp: i64,
+    total_amount: u64,
+    locked_profit_tracker: LockedProfitTracker,
+) -> VaultSnapshot {
+    let fixture = PoolFixture {
+        vault_a: VaultFixture {
+            total_amount,
+            locked_profit_tracker,
+            ..usdc_vault(POOL_LP_AMOUNT)
+        },
+        unix_timestamp,
+        ..usdc_wsol_pool()
+    };
+    let [vault_a, _] = fixture.snapshot().vault_snapshots();
+
+    vault_a
+}
+
+/// USDC vault without locked profit, so the virtual price is the total amount per LP
+fn unlocked_usdc_vault_snapshot(unix_timestamp: i64, total_amount: u64) -> VaultSnapshot {
+    usdc_vault_snapshot(unix_timestamp, total_amount, locked_profit(0, 0))
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-9,
+        "{} is not close to {}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn yield_over_a_year() {
+    let total_amount = usdc_vault(POOL_LP_AMOUNT).total_amount;
+    let snapshots = [
+        unlocked_usdc_vault_snapshot(UNIX_TIMESTAMP, total_amount),
+        unlocked_usdc_vault_snapshot(
+            UNIX_TIMESTAMP + SECONDS_PER_YEAR as i64,
+            total_amount / 100 * 110,
+        ),
+    ];
+
+    let vault_yield = compute_vault_yield_from_snapshots(&snapshots).unwrap();
+
+    assert_close(vault_yield.apy, 0.1);
+    assert_eq!(vault_yield.points.len(), 2);
+}
+
+#[test]
+fn yield_is_annualized_and_compounded() {
+    let total_amount = usdc_vault(POOL_LP_AMOUNT).total_amount;
+    let snapshots = [
+        unlocked_usdc_vault_snapshot(UNIX_TIMESTAMP, total_amount),
+        unlocked_usdc_vault_snapshot(
+            UNIX_TIMESTAMP + SECONDS_PER_YEAR as i64 / 2,
+            total_amount / 100 * 105,
+        ),
+    ];
+
+    let vault_yield = compute_vault_yield_from_snapshots(&snapshots).unwrap();
+
+    assert_close(vault_yield.apy, 1.05f64.powi(2) - 1.0);
+}
+
+#[test]
+fn yield_sorts_the_snapshots() {
+    let total_amount = usdc_vault(POOL_LP_AMOUNT).total_amount;
+    let later = usdc_vault_snapshot(
+        UNIX_TIMESTAMP + 86_400,
+        total_amount + 1_000_000_000,
+        LockedProfitTracker {
+            last_report: UNIX_TIMESTAMP as u64 + 86_400,
+            ..locked_profit(1_000_000, 0)
+        },
+    );
+    let earlier = unlocked_usdc_vault_snapshot(UNIX_TIMESTAMP, total_amount);
+
+    let vault_yield = compute_vault_yield_from_snapshots(&[later, earlier]).unwrap();
+
+    assert_eq!(
+        vault_yield
+            .points
+            .iter()
+            .map(|point| point.unix_timestamp)
+            .collect::<Vec<_>>(),
+        vec![UNIX_TIMESTAMP, UNIX_TIMESTAMP + 86_400]
+    );
+    assert!(vault_yield.apy > 0.0);
+    // The locked profit is the one of the latest snapshot
+    assert_eq!(vault_yield.locked_profit_release.locked_profit, 1_000_000);
+}
+
+#[test]
+fn yield_needs_snapshots_over_time_of_one_vault() {
+    let total_amount = usdc_vault(POOL_LP_AMOUNT).total_amount;
+    let snapshot = unlocked_usdc_vault_snapshot(UNIX_TIMESTAMP, total_amount);
+    let [_, wsol_vault_snapshot] = usdc_wsol_pool().snapshot().vault_snapshots();
+
+    assert!(compute_vault_yield_from_snapshots(&[]).is_err());
+    assert!(compute_vault_yield_from_snapshots(std::slice::from_ref(&snapshot)).is_err());
+    assert!(compute_vault_yield_from_snapshots(&[snapshot.clone(), snapshot.clone()]).is_err());
+    assert!(compute_vault_yield_from_snapshots(&[snapshot, wsol_vault_snapshot]).is_err());
+}
+
+#[test]
+fn locked_profit_release_of_the_usdc_vault() {
+    let [usdc_vault_snapshot, _] = usdc_wsol_pool().snapshot().vault_snapshots();
+    let vault_quote_data = usdc_vault_snapshot.to_vault_quote_data().unwrap();
+
+    let release = get_locked_profit_release(&vault_quote_data).unwrap();
+
+    // 88_214_553 reported an hour ago, with a sixth of the 6 hours degradation elapsed
+    assert_eq!(release.locked_profit, 73_512_127);
+    assert_eq!(
+        release.fully_released_at,
+        Some(UNIX_TIMESTAMP as u64 - 3_600 + 21_601)
+    );
+    let vault = usdc_vault(POOL_LP_AMOUNT);
+    assert_eq!(
+        release.virtual_price_after_release,
+        vault.total_amount as f64 / vault.lp_mint_supply as f64
+    );
+}
+
+#[test]
+fn locked_profit_is_released_after_the_degradation() {
+    let total_amount = usdc_vault(POOL_LP_AMOUNT).total_amount;
+    let snapshot = usdc_vault_snapshot(
+        UNIX_TIMESTAMP,
+        total_amount,
+        locked_profit(88_214_553, 21_601),
+    );
+
+    let release = get_locked_profit_release(&snapshot.to_vault_quote_data().unwrap()).unwrap();
+
+    assert_eq!(release.locked_profit, 0);
+    assert_eq!(release.fully_released_at, Some(UNIX_TIMESTAMP as u64));
+}
+
+#[test]
+fn locked_profit_without_degradation_is_never_released() {
+    let total_amount = usdc_vault(POOL_LP_AMOUNT).total_amount;
+    let tracker = LockedProfitTracker {
+        locked_profit_degradation: 0,
+        ..locked_profit(88_214_553, 3_600)
+    };
+    let snapshot = usdc_vault_snapshot(UNIX_TIMESTAMP, total_amount, tracker);
+
+    let release = get_locked_profit_release(&snapshot.to_vault_quote_data().unwrap()).unwrap();
+
+    assert_eq!(release.locked_profit, 88_214_553);
+    assert_eq!(release.fully_released_at, None);
+}
+
+#[test]
+fn vault_without_lp_has_no_price() {
+    let mut vault_quote_data = unlocked_usdc_vault_snapshot(UNIX_TIMESTAMP, 1_000_000)
+        .to_vault_quote_data()
+        .unwrap();
+    vault_quote_data.lp_mint_supply = 0;
+
+    assert!(get_virtual_price(&vault_quote_data).is_err());
+    assert!(get_locked_profit_release(&vault_quote_data).is_err());
+}
+
+#[test]
+fn vault_snapshot_json_round_trip() {
+    let [snapshot, _] = usdc_wsol_pool().snapshot().vault_snapshots();
+
+    let json = snapshot.to_json().unwrap();
+
+    assert_eq!(VaultSnapshot::from_json(&json).unwrap(), snapshot);
+    let mut other_version = snapshot;
+    other_version.version += 1;
+    assert!(VaultSnapshot::from_json(&other_version.to_json().unwrap()).is_err());
+}
+
+#[test]
+fn vault_snapshot_with_swapped_accounts_is_rejected() {
+    let [snapshot, _] = usdc_wsol_pool().snapshot().vault_snapshots();
+    let swapped = VaultSnapshot {
+        lp_mint: snapshot.token_vault.clone(),
+        token_vault: snapshot.lp_mint.clone(),
+        ..snapshot
+    };
+
+    assert!(swapped.to_vault_quote_data().is_err());
+}
@@ -0,0 +1,88 @@
+use crate::state::Vault;
+use anchor_spl::token::TokenAccount;
+use anyhow::{ensure, Context};
+use solana_program::clock::Clock;
+use std::convert::TryInto;
+
+#[derive(Clone)]
+pub struct VaultQuoteData {
+    /// Vault state
+    pub vault: Vault,
+    /// Lp supply of mint of the vault
+    pub lp_mint_supply: u64,
+    /// Token account of the vault, holding the vault reserve
+    pub token_vault: TokenAccount,
+    /// Clock sysvar, read together with the accounts above
+    pub clock: Clock,
+}
+
+#[derive(Debug, Clone)]
+pub struct VaultQuoteResult {
+    /// Token amount deposited or withdrawn
+    pub token_amount: u64,
+    /// Vault LP amount minted or burnt
+    pub lp_amount: u64,
+}
+
+/// Quote the vault LP minted for depositing `token_amount`. Deposits are blocked while the vault is
+/// disabled, and the LP is priced on the unlocked amount of the vault.
+pub fn compute_deposit_quote(
+    token_amount: u64,
+    vault_quote_data: &VaultQuoteData,
+) -> anyhow::Result<VaultQuoteResult> {
+    let VaultQuoteData {
+        vault,
+        lp_mint_supply,
+        clock,
+        ..
+    } = vault_quote_data;
+
+    ensure!(vault.enabled == 1, "Vault disabled");
+    ensure!(*lp_mint_supply > 0, "Vault has no LP supply");
+
+    let current_time: u64 = clock.unix_timestamp.try_into()?;
+
+    let lp_amount = vault
+        .get_unmint_amount(current_time, token_amount, *lp_mint_supply)
+        .context("Fail to get lp amount")?;
+
+    Ok(VaultQuoteResult {
+        token_amount,
+        lp_amount,
+    })
+}
+
+/// Quote the tokens returned for burning `lp_amount` of vault LP. Withdrawals are allowed while the
+/// vault is disabled, but are paid from the vault reserve only.
+pub fn compute_withdraw_quote(
+    lp_amount: u64,
+    vault_quote_data: &VaultQuoteData,
+) -> anyhow::Result<VaultQuoteResult> {
+    let VaultQuoteData {
+        vault,
+        lp_mint_supply,
+        token_vault,
+        clock,
+    } = vault_quote_data;
+
+    ensure!(lp_amount <= *lp_mint_supply, "LP amount > vault LP supply");
+
+    let current_time: u64 = clock.unix_timestamp.try_into()?;
+
+    let token_amount = vault
+        .get_amount_by_share(current_time, lp_amount, *lp_mint_supply)
+        .context("Fail to get token amount")?;
+
+    ensure!(
+        token_amount <= token_vault.amount,
+        "Withdraw amount > vault reserve"
+    );
+
+    Ok(VaultQuoteResult {
+        token_amount,
+        lp_amount,
+    })
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,116 @@
+use super::*;
+use crate::state::LockedProfitTracker;
+use crate::test_fixtures::*;
+
+/// Pool LP amount of the USDC vault fixture, any amount gives the same vault
+const POOL_LP_AMOUNT: u64 = 1_522_664_910_001;
+
+fn vault_quote_data(vault_a: VaultFixture) -> VaultQuoteData {
+    let fixture = PoolFixture {
+        vault_a,
+        ..usdc_wsol_pool()
+    };
+    let [vault_a, _] = fixture.snapshot().vault_snapshots();
+
+    vault_a.to_vault_quote_data().unwrap()
+}
+
+fn usdc_vault_quote_data(locked_profit_tracker: LockedProfitTracker) -> VaultQuoteData {
+    vault_quote_data(VaultFixture {
+        locked_profit_tracker,
+        ..usdc_vault(POOL_LP_AMOUNT)
+    })
+}
+
+fn unlocked_usdc_vault_quote_data() -> VaultQuoteData {
+    usdc_vault_quote_data(locked_profit(0, 0))
+}
+
+#[test]
+fn unlocked_vault_prices_the_lp_on_the_total_amount() {
+    let vault_quote_data = unlocked_usdc_vault_quote_data();
+    let total_amount = vault_quote_data.vault.total_amount as u128;
+    let lp_mint_supply = vault_quote_data.lp_mint_supply as u128;
+
+    let withdraw = compute_withdraw_quote(1_000_000_000, &vault_quote_data).unwrap();
+    assert_eq!(
+        withdraw.token_amount as u128,
+        1_000_000_000 * total_amount / lp_mint_supply
+    );
+
+    let deposit = compute_deposit_quote(1_000_000_000, &vault_quote_data).unwrap();
+    assert_eq!(
+        deposit.lp_amount as u128,
+        1_000_000_000 * lp_mint_supply / total_amount
+    );
+}
+
+#[test]
+fn disabled_vault_rejects_deposits_but_allows_withdrawals() {
+    let enabled = unlocked_usdc_vault_quote_data();
+    let mut disabled = enabled.clone();
+    disabled.vault.enabled = 0;
+
+    assert!(compute_deposit_quote(1_000_000_000, &enabled).is_ok());
+    assert!(compute_deposit_quote(1_000_000_000, &disabled).is_err());
+
+    assert_eq!(
+        compute_withdraw_quote(1_000_000_000, &disabled)
+            .unwrap()
+            .token_amount,
+        compute_withdraw_quote(1_000_000_000, &enabled)
+            .unwrap()
+            .token_amount
+    );
+}
+
+#[test]
+fn locked_profit_lowers_the_share_price() {
+    let unlocked = unlocked_usdc_vault_quote_data();
+    // Profit reported just now, none of it is released yet
+    let locked = usdc_vault_quote_data(locked_profit(1_000_000_000_000, 0));
+
+    let unlocked_withdraw = compute_withdraw_quote(1_000_000_000, &unlocked).unwrap();
+    let locked_withdraw = compute_withdraw_quote(1_000_000_000, &locked).unwrap();
+    assert!(locked_withdraw.token_amount < unlocked_withdraw.token_amount);
+
+    let unlocked_deposit = compute_deposit_quote(1_000_000_000, &unlocked).unwrap();
+    let locked_deposit = compute_deposit_quote(1_000_000_000, &locked).unwrap();
+    assert!(locked_deposit.lp_amount > unlocked_deposit.lp_amount);
+
+    // Once released, the profit counts again
+    let released = usdc_vault_quote_data(locked_profit(1_000_000_000_000, 86_400));
+    assert_eq!(
+        compute_withdraw_quote(1_000_000_000, &released)
+            .unwrap()
+            .token_amount,
+        unlocked_withdraw.token_amount
+    );
+}
+
+#[test]
+fn withdrawal_above_the_vault_reserve_is_rejected() {
+    let vault_quote_data = unlocked_usdc_vault_quote_data();
+    let lp_mint_supply = vault_quote_data.lp_mint_supply;
+
+    // The reserve holds less than a third of the total amount, the rest is lent to strategies
+    let withdraw = compute_withdraw_quote(lp_mint_supply / 4, &vault_quote_data).unwrap();
+    assert!(withdraw.token_amount <= vault_quote_data.token_vault.amount);
+    assert!(compute_withdraw_quote(lp_mint_supply / 2, &vault_quote_data).is_err());
+}
+
+#[test]
+fn withdrawal_above_the_lp_supply_is_rejected() {
+    // Everything in the reserve, so only the LP supply limits the withdrawal
+    let usdc_vault = usdc_vault(POOL_LP_AMOUNT);
+    let vault_quote_data = vault_quote_data(VaultFixture {
+        token_vault_amount: usdc_vault.total_amount,
+        locked_profit_tracker: locked_profit(0, 0),
+        ..usdc_vault
+    });
+    let lp_mint_supply = vault_quote_data.lp_mint_supply;
+
+    let withdraw = compute_withdraw_quote(lp_mint_supply, &vault_quote_data).unwrap();
+    assert_eq!(withdraw.token_amount, vault_quote_data.vault.total_amount);
+    assert!(compute_withdraw_quote(lp_mint_supply + 1, &vault_quote_data).is_err());
+}
@@ -0,0 +1,110 @@
+use crate::state::Strategy;
+use crate::vault_quote::VaultQuoteData;
+use anchor_spl::token::TokenAccount;
+use anyhow::{ensure, Context};
+use solana_program::pubkey::Pubkey;
+use std::convert::TryInto;
+
+/// Liquidity of one strategy of the vault
+#[derive(Debug, Clone, Copy)]
+pub struct StrategyLiquidity {
+    /// Strategy address
+    pub strategy: Pubkey,
+    /// Liquidity in the strategy at the time of the last deposit or withdrawal
+    pub current_liquidity: u64,
+}
+
+/// Accounting of a vault against its reserve and strategies
+#[derive(Debug, Clone)]
+pub struct VaultSolvencyReport {
+    /// Total liquidity claimed by the vault
+    pub total_amount: u64,
+    /// Tokens in the vault reserve, immediately withdrawable
+    pub reserve_amount: u64,
+    /// Liquidity of every strategy of the vault
+    pub strategies: Vec<StrategyLiquidity>,
+    /// Reserve plus the liquidity of all strategies
+    pub accounted_amount: u64,
+    /// Total amount minus accounted amount. Positive when the vault claims more than it holds.
+    pub mismatch: i128,
+    /// Share of the total amount that is immediately withdrawable
+    pub withdrawable_ratio: f64,
+    /// Vault LP held by the fee vault
+    pub fee_vault_lp_amount: u64,
+    /// Tokens the fee vault LP can be withdrawn for
+    pub fee_vault_token_amount: u64,
+}
+
+impl VaultSolvencyReport {
+    /// Check whether the total amount matches the reserve plus the strategies liquidity
+    pub fn is_consistent(&self) -> bool {
+        self.mismatch == 0
+    }
+}
+
+/// Check the vault total amount against its reserve and the liquidity of its strategies
+pub fn check_vault_solvency(
+    vault_quote_data: &VaultQuoteData,
+    fee_vault: &TokenAccount,
+    strategies: &[(Pubkey, Strategy)],
+) -> anyhow::Result<VaultSolvencyReport> {
+    let VaultQuoteData {
+        vault,
+        lp_mint_supply,
+        token_vault,
+        clock,
+    } = vault_quote_data;
+
+    // The fee vault holds vault LP, valued at the vault share price below
+    ensure!(
+        fee_vault.mint == vault.lp_mint,
+        "Fee vault mint {} is not the vault LP mint {}",
+        fee_vault.mint,
+        vault.lp_mint
+    );
+
+    let current_time: u64 = clock.unix_timestamp.try_into()?;
+
+    let strategies: Vec<StrategyLiquidity> = strategies
+        .iter()
+        .map(|(strategy, state)| StrategyLiquidity {
+            strategy: *strategy,
+            current_liquidity: state.current_liquidity,
+        })
+        .collect();
+
+    let accounted_amount = strategies
+        .iter()
+        .try_fold(token_vault.amount, |total, strategy| {
+            total.checked_add(strategy.current_liquidity)
+        })
+        .context("Fail to sum strategies liquidity")?;
+
+    let withdrawable_ratio = if vault.total_amount == 0 {
+        1.0
+    } else {
+        token_vault.amount as f64 / vault.total_amount as f64
+    };
+
+    let fee_vault_token_amount = if *lp_mint_supply == 0 {
+        0
+    } else {
+        vault
+            .get_amount_by_share(current_time, fee_vault.amount, *lp_mint_supply)
+            .context("Fail to get fee vault token amount")?
+    };
+
+    Ok(VaultSolvencyReport {
+        total_amount: vault.total_amount,
+        reserve_amount: token_vault.amount,
+        strategies,
+        accounted_amount,
+        mismatch: i128::from(vault.total_amount) - i128::from(accounted_amount),
+        withdrawable_ratio,
+        fee_vault_lp_amount: fee_vault.amount,
+        fee_vault_token_amount,
+    })
+}
+
+#[cfg(test)]
+mod tests;
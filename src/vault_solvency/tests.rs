@@ -0,0 +1,149 @@
+use super::*;
+use crate::decoder::decode_token_account;
+use crate::quote_service::with_amount;
+use crate::state::{StrategyType, MAX_BUMPS};
+use crate::test_fixtures::*;
+
+/// USDC vault: 9_836_420_118_733 in total, 2_950_926_035_619 of it in the reserve
+fn usdc_vault_quote_data() -> VaultQuoteData {
+    let [vault_a, _] = usdc_wsol_pool().snapshot().vault_snapshots();
+
+    vault_a.to_vault_quote_data().unwrap()
+}
+
+fn strategy(current_liquidity: u64) -> (Pubkey, Strategy) {
+    (
+        Pubkey::new_unique(),
+        Strategy {
+            reserve: Pubkey::new_unique(),
+            collateral_vault: Pubkey::new_unique(),
+            strategy_type: StrategyType::SolendWithoutLM,
+            current_liquidity,
+            bumps: [0; MAX_BUMPS],
+            vault: Pubkey::new_unique(),
+            is_disable: 0,
+        },
+    )
+}
+
+/// Token account holding `lp_amount` of the USDC vault LP
+fn fee_vault(lp_amount: u64) -> TokenAccount {
+    let pool_vault_lp_token =
+        decode_token_account(&usdc_wsol_pool().snapshot().pool_vault_a_lp_token.data).unwrap();
+
+    with_amount(&pool_vault_lp_token, lp_amount).unwrap()
+}
+
+#[test]
+fn vault_backed_by_reserve_and_strategies_is_consistent() {
+    let vault_quote_data = usdc_vault_quote_data();
+    let strategies = [strategy(5_000_000_000_000), strategy(1_885_494_083_114)];
+
+    let report = check_vault_solvency(&vault_quote_data, &fee_vault(0), &strategies).unwrap();
+
+    assert!(report.is_consistent());
+    assert_eq!(report.total_amount, 9_836_420_118_733);
+    assert_eq!(report.reserve_amount, 2_950_926_035_619);
+    assert_eq!(report.accounted_amount, 9_836_420_118_733);
+    assert_eq!(
+        report
+            .strategies
+            .iter()
+            .map(|strategy| (strategy.strategy, strategy.current_liquidity))
+            .collect::<Vec<_>>(),
+        strategies
+            .iter()
+            .map(|(address, strategy)| (*address, strategy.current_liquidity))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        report.withdrawable_ratio,
+        2_950_926_035_619.0 / 9_836_420_118_733.0
+    );
+}
+
+#[test]
+fn missing_strategy_liquidity_is_a_positive_mismatch() {
+    let vault_quote_data = usdc_vault_quote_data();
+
+    let report = check_vault_solvency(
+        &vault_quote_data,
+        &fee_vault(0),
+        &[strategy(5_000_000_000_000)],
+    )
+    .unwrap();
+
+    assert!(!report.is_consistent());
+    assert_eq!(report.mismatch, 1_885_494_083_114);
+}
+
+#[test]
+fn extra_strategy_liquidity_is_a_negative_mismatch() {
+    let vault_quote_data = usdc_vault_quote_data();
+
+    let report = check_vault_solvency(
+        &vault_quote_data,
+        &fee_vault(0),
+        &[strategy(7_000_000_000_000)],
+    )
+    .unwrap();
+
+    assert_eq!(report.mismatch, -114_505_916_886);
+}
+
+#[test]
+fn fee_vault_is_valued_at_the_unlocked_share_price() {
+    let vault_quote_data = usdc_vault_quote_data();
+    let fee_vault_lp_amount = 1_000_000_000;
+
+    let report =
+        check_vault_solvency(&vault_quote_data, &fee_vault(fee_vault_lp_amount), &[]).unwrap();
+
+    assert_eq!(report.fee_vault_lp_amount, fee_vault_lp_amount);
+    assert_eq!(
+        Some(report.fee_vault_token_amount),
+        vault_quote_data.vault.get_amount_by_share(
+            UNIX_TIMESTAMP as u64,
+            fee_vault_lp_amount,
+            vault_quote_data.lp_mint_supply
+        )
+    );
+    // The locked profit is left out, so the LP is worth less than total amount / supply
+    assert!(
+        (report.fee_vault_token_amount as f64)
+            < fee_vault_lp_amount as f64 * vault_quote_data.vault.total_amount as f64
+                / vault_quote_data.lp_mint_supply as f64
+    );
+}
+
+#[test]
+fn empty_vault() {
+    let mut vault_quote_data = usdc_vault_quote_data();
+    vault_quote_data.vault.total_amount = 0;
+    vault_quote_data.vault.locked_profit_tracker = locked_profit(0, 0);
+    vault_quote_data.lp_mint_supply = 0;
+    vault_quote_data.token_vault = with_amount(&vault_quote_data.token_vault, 0).unwrap();
+
+    let report = check_vault_solvency(&vault_quote_data, &fee_vault(1_000), &[]).unwrap();
+
+    assert!(report.is_consistent());
+    assert_eq!(report.withdrawable_ratio, 1.0);
+    assert_eq!(report.fee_vault_token_amount, 0);
+}
+
+#[test]
+fn strategies_liquidity_overflow_is_rejected() {
+    let vault_quote_data = usdc_vault_quote_data();
+
+    assert!(
+        check_vault_solvency(&vault_quote_data, &fee_vault(0), &[strategy(u64::MAX)],).is_err()
+    );
+}
+
+#[test]
+fn fee_vault_of_another_mint_is_rejected() {
+    let vault_quote_data = usdc_vault_quote_data();
+    let token_account = with_amount(&vault_quote_data.token_vault, 1_000_000_000).unwrap();
+
+    assert!(check_vault_solvency(&vault_quote_data, &token_account, &[]).is_err());
+}